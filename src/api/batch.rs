@@ -0,0 +1,144 @@
+use super::changes::ChangeLog;
+use super::error::ApiError;
+use super::json_body;
+use super::openapi::{Endpoint, Response};
+use crate::lineage::{KillError, Lineage, ResolveError, SuccessionChanges};
+use crate::persistence::Event;
+use async_std::sync::{Arc, RwLock};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use warp::http::StatusCode;
+use warp::Filter;
+
+/// Body of POST /kill/batch, with ids or names
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct KillBatchRequest {
+    names: Vec<String>,
+}
+
+/// What happened to each name of the batch, using the same codes as the errors
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum KillOutcome {
+    Killed,
+    /// Valid, but not applied because another name of the batch was not found
+    Skipped,
+    PersonNotFound,
+    /// Several people have the name, nothing was applied either
+    AmbiguousName,
+    AlreadyDead,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct NameOutcome {
+    name: String,
+    outcome: KillOutcome,
+}
+
+/// Body of the POST /kill/batch response, `results` are in the same order as the request names.
+/// `succession_changes` is empty when the batch was not applied.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct KillBatchResponse {
+    applied: bool,
+    results: Vec<NameOutcome>,
+    succession_changes: SuccessionChanges,
+}
+
+/// POST /kill/batch with a `{"names": [...]}` body => 200 OK if everybody was found and the
+/// batch was applied, 422 UNPROCESSABLE_ENTITY and nothing applied otherwise
+pub fn kill_batch_route(
+    lineage_ref: Arc<RwLock<Lineage>>,
+    changes: Arc<ChangeLog>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("kill" / "batch")
+        .and(warp::post())
+        .and(json_body::<KillBatchRequest>())
+        .and(warp::any().map(move || lineage_ref.clone()))
+        .and(warp::any().map(move || changes.clone()))
+        .and_then(kill_batch)
+}
+
+async fn kill_batch(
+    request: KillBatchRequest,
+    lineage: Arc<RwLock<Lineage>>,
+    changes: Arc<ChangeLog>,
+) -> Result<impl warp::Reply, Infallible> {
+    // a single write lock for the whole batch, readers never see it half applied
+    let mut lineage = lineage.write().await;
+    let resolved: Vec<Result<String, ResolveError>> = request
+        .names
+        .iter()
+        .map(|name| lineage.resolve(name).map(|person| person.id().to_string()))
+        .collect();
+    // names which can not be resolved are not found by the lineage either
+    let ids: Vec<&str> = resolved
+        .iter()
+        .zip(&request.names)
+        .map(|(id, name)| id.as_deref().unwrap_or(name))
+        .collect();
+    let killed = changes.apply(&mut lineage, |lineage| {
+        let (outcomes, changes) = lineage.kill_batch(&ids)?;
+        let events = ids
+            .iter()
+            .zip(&outcomes)
+            .filter(|(_, outcome)| outcome.is_ok())
+            .map(|(id, _)| Event::Kill { id: id.to_string() })
+            .collect();
+        Ok((outcomes, changes, events))
+    });
+    let (applied, outcomes, succession_changes) = match killed {
+        Ok((outcomes, changes)) => (true, outcomes, changes),
+        Err(outcomes) => (false, outcomes, vec![]),
+    };
+    let results: Vec<NameOutcome> = request
+        .names
+        .into_iter()
+        .zip(outcomes)
+        .zip(resolved)
+        .map(|((name, outcome), resolved)| {
+            let outcome = match outcome {
+                Ok(()) if applied => KillOutcome::Killed,
+                Ok(()) => KillOutcome::Skipped,
+                Err(KillError::PersonNotFound)
+                    if matches!(resolved, Err(ResolveError::Ambiguous(_))) =>
+                {
+                    KillOutcome::AmbiguousName
+                }
+                Err(KillError::PersonNotFound) => KillOutcome::PersonNotFound,
+                Err(KillError::PersonAlreadyDead) => KillOutcome::AlreadyDead,
+            };
+            NameOutcome { name, outcome }
+        })
+        .collect();
+    drop(lineage);
+    let status = if applied {
+        StatusCode::OK
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    };
+    Ok(warp::reply::with_status(
+        warp::reply::json(&KillBatchResponse {
+            applied,
+            results,
+            succession_changes,
+        }),
+        status,
+    ))
+}
+
+pub fn endpoints() -> Vec<Endpoint> {
+    vec![
+        Endpoint::new("post", "/kill/batch", "Kills everybody at once, or nobody")
+            .json_body::<KillBatchRequest>()
+            .response(Response::json::<KillBatchResponse>(
+                200,
+                "Everybody was found, the batch was applied",
+            ))
+            .response(Response::json::<KillBatchResponse>(
+                422,
+                "Somebody was not found or is ambiguous, nothing was applied",
+            ))
+            .response(Response::json::<ApiError>(400, "INVALID_BODY")),
+    ]
+}
@@ -0,0 +1,75 @@
+use crate::lineage::{KillError, Lineage, ReviveError, SuccessionChange, SuccessionChanges};
+use crate::persistence::{Event, Journal};
+use tokio::sync::broadcast;
+
+/// How many heir changes a slow subscriber can lag behind before missing some
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Where the changes made to the lineage go: persisted to the journal and the heir changes they
+/// cause published to the subscribers of the succession stream
+#[derive(Debug)]
+pub struct ChangeLog {
+    journal: Journal,
+    sender: broadcast::Sender<SuccessionChange>,
+}
+
+impl ChangeLog {
+    pub fn new(journal: Journal) -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        ChangeLog { journal, sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<SuccessionChange> {
+        self.sender.subscribe()
+    }
+
+    /// Applies `mutation` to the lineage, which must be already locked for writing so the journal
+    /// keeps the order changes were applied in. When it succeeds the events it returns are
+    /// persisted and the heir changes it reports are published, then returned with its result.
+    pub fn apply<T, E>(
+        &self,
+        lineage: &mut Lineage,
+        mutation: impl FnOnce(&mut Lineage) -> Result<(T, SuccessionChanges, Vec<Event>), E>,
+    ) -> Result<(T, SuccessionChanges), E> {
+        let (result, changes, events) = mutation(lineage)?;
+        for event in events {
+            // the change is already visible to the other requests, failing to persist it does
+            // not fail the request
+            if let Err(e) = self.journal.record(&event) {
+                log::error!("Could not persist {:?}: {}", event, e);
+            }
+        }
+        for change in &changes {
+            // an error only means nobody is subscribed
+            let _ = self.sender.send(change.clone());
+        }
+        Ok((result, changes))
+    }
+
+    /// Kills the person with the id, or the name, `key`
+    pub fn kill(&self, lineage: &mut Lineage, key: &str) -> Result<SuccessionChanges, KillError> {
+        self.apply(lineage, |lineage| {
+            let changes = lineage.kill(key)?;
+            let event = Event::Kill {
+                id: key.to_string(),
+            };
+            Ok(((), changes, vec![event]))
+        })
+        .map(|((), changes)| changes)
+    }
+
+    pub fn revive(
+        &self,
+        lineage: &mut Lineage,
+        key: &str,
+    ) -> Result<SuccessionChanges, ReviveError> {
+        self.apply(lineage, |lineage| {
+            let changes = lineage.revive(key)?;
+            let event = Event::Revive {
+                id: key.to_string(),
+            };
+            Ok(((), changes, vec![event]))
+        })
+        .map(|((), changes)| changes)
+    }
+}
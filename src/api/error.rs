@@ -0,0 +1,253 @@
+use super::model::PersonView;
+use crate::lineage::{InsertError, Lineage, ResolveError, UnknownName, UnlinkError};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::convert::Infallible;
+use warp::http::StatusCode;
+use warp::{Rejection, Reply};
+
+/// Machine readable error codes, so clients do not have to parse the messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    PersonNotFound,
+    NoSuccessor,
+    AmbiguousName,
+    AlreadyDead,
+    AlreadyAlive,
+    InvalidQuery,
+    InvalidBody,
+    InvalidName,
+    PersonAlreadyExists,
+    IdConflict,
+    SexMismatch,
+    Cycle,
+    RelationshipAlreadyExists,
+    ParentAlreadySet,
+    RelationshipNotFound,
+    RouteNotFound,
+    MethodNotAllowed,
+    Internal,
+}
+
+impl ErrorCode {
+    pub fn status(self) -> StatusCode {
+        match self {
+            ErrorCode::PersonNotFound
+            | ErrorCode::NoSuccessor
+            | ErrorCode::RelationshipNotFound
+            | ErrorCode::RouteNotFound => StatusCode::NOT_FOUND,
+            ErrorCode::AmbiguousName
+            | ErrorCode::AlreadyDead
+            | ErrorCode::AlreadyAlive
+            | ErrorCode::IdConflict
+            | ErrorCode::PersonAlreadyExists
+            | ErrorCode::SexMismatch
+            | ErrorCode::RelationshipAlreadyExists
+            | ErrorCode::ParentAlreadySet => StatusCode::CONFLICT,
+            ErrorCode::InvalidQuery | ErrorCode::InvalidBody => StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidName | ErrorCode::Cycle => StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCode::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// The code as it appears in the JSON bodies, e.g. PERSON_NOT_FOUND
+    pub fn name(self) -> String {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::String(name)) => name,
+            _ => unreachable!("error codes serialize to strings"),
+        }
+    }
+}
+
+/// JSON body of every error returned by the API
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ApiError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// What the client could do to fix the request, if anything
+    pub suggestion: Option<String>,
+    /// Names of the lineage close to an unknown name, best first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub did_you_mean: Vec<String>,
+    /// People bearing an ambiguous name
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<PersonView>,
+}
+
+impl ApiError {
+    pub fn new<S: Into<String>>(code: ErrorCode, message: S, suggestion: Option<S>) -> Self {
+        ApiError {
+            code,
+            message: message.into(),
+            suggestion: suggestion.map(Into::into),
+            did_you_mean: vec![],
+            candidates: vec![],
+        }
+    }
+
+    pub fn person_not_found(name: &str) -> Self {
+        ApiError::new(
+            ErrorCode::PersonNotFound,
+            format!("{} is not part of the lineage", name),
+            Some("Check the spelling, names include the house, e.g. Eddard Stark".to_string()),
+        )
+    }
+
+    /// The person exists but nobody alive is in line to succeed it
+    pub fn no_successor(name: &str) -> Self {
+        ApiError::new(
+            ErrorCode::NoSuccessor,
+            format!("{} has no living heir", name),
+            None,
+        )
+    }
+
+    /// PERSON_NOT_FOUND suggesting the names close to the unknown one, if any
+    pub fn unknown_name(unknown: UnknownName) -> Self {
+        if unknown.suggestions.is_empty() {
+            return ApiError::person_not_found(&unknown.name);
+        }
+        ApiError {
+            code: ErrorCode::PersonNotFound,
+            message: format!("{} is not part of the lineage", unknown.name),
+            suggestion: Some(format!("Did you mean {}?", unknown.suggestions.join(", "))),
+            did_you_mean: unknown.suggestions,
+            candidates: vec![],
+        }
+    }
+
+    /// PERSON_NOT_FOUND or AMBIGUOUS_NAME with the records of the people bearing the name
+    pub fn unresolved(lineage: &Lineage, error: ResolveError) -> Self {
+        let ambiguous = match error {
+            ResolveError::Unknown(unknown) => return ApiError::unknown_name(unknown),
+            ResolveError::Ambiguous(ambiguous) => ambiguous,
+        };
+        ApiError {
+            code: ErrorCode::AmbiguousName,
+            message: format!("{} is the name of several people", ambiguous.name),
+            suggestion: Some(format!(
+                "Use the id of one of them: {}",
+                ambiguous.candidates.join(", ")
+            )),
+            did_you_mean: vec![],
+            candidates: ambiguous
+                .candidates
+                .iter()
+                .filter_map(|id| lineage.get_from_id(id))
+                .map(|person| PersonView::new(lineage, person))
+                .collect(),
+        }
+    }
+
+    pub fn already_dead(name: &str) -> Self {
+        ApiError::new(
+            ErrorCode::AlreadyDead,
+            format!("{} was already dead", name),
+            Some(format!(
+                "GET /v2/successor?name={} tells who inherited",
+                name.replace(' ', "%20")
+            )),
+        )
+    }
+}
+
+impl From<InsertError> for ApiError {
+    fn from(error: InsertError) -> Self {
+        let (code, suggestion) = match &error {
+            InsertError::MissingHouseName(_) => (
+                ErrorCode::InvalidName,
+                Some("Names need a first and a house name, e.g. Eddard Stark"),
+            ),
+            InsertError::PersonAlreadyExists(_) => (ErrorCode::PersonAlreadyExists, None),
+            InsertError::SexMismatch(_) => (
+                ErrorCode::SexMismatch,
+                Some("Use the sex the person already has, see GET /people/{name}"),
+            ),
+            InsertError::Cycle { .. } => (ErrorCode::Cycle, None),
+            InsertError::RelationshipAlreadyExists { .. } => {
+                (ErrorCode::RelationshipAlreadyExists, None)
+            }
+            InsertError::AmbiguousName { .. } => (
+                ErrorCode::AmbiguousName,
+                Some("Give the id of the person, e.g. parent_id or child_id"),
+            ),
+            InsertError::IdConflict { .. } => (ErrorCode::IdConflict, None),
+            InsertError::ParentAlreadySet { .. } => (
+                ErrorCode::ParentAlreadySet,
+                Some("Remove the existing relationship first with DELETE /relationships"),
+            ),
+        };
+        ApiError {
+            code,
+            message: error.to_string(),
+            suggestion: suggestion.map(str::to_string),
+            did_you_mean: vec![],
+            candidates: vec![],
+        }
+    }
+}
+
+impl From<UnlinkError> for ApiError {
+    fn from(error: UnlinkError) -> Self {
+        match error {
+            UnlinkError::PersonNotFound(name) => ApiError::person_not_found(&name),
+            UnlinkError::NotParentOf { parent, child } => ApiError::new(
+                ErrorCode::RelationshipNotFound,
+                format!("{} is not a parent of {}", parent, child),
+                None,
+            ),
+        }
+    }
+}
+
+impl Reply for ApiError {
+    fn into_response(self) -> warp::reply::Response {
+        warp::reply::with_status(warp::reply::json(&self), self.code.status()).into_response()
+    }
+}
+
+/// Turns warp rejections (unmatched route, missing query parameter...) into JSON errors
+pub async fn handle_rejection(rejection: Rejection) -> Result<impl Reply, Infallible> {
+    let error = if rejection.is_not_found() {
+        ApiError::new(
+            ErrorCode::RouteNotFound,
+            "No route matches the request path",
+            None,
+        )
+    } else if let Some(invalid_query) = rejection.find::<warp::reject::InvalidQuery>() {
+        ApiError::new(
+            ErrorCode::InvalidQuery,
+            invalid_query.to_string(),
+            Some(
+                "Pass the person as an url encoded query parameter, e.g. ?name=Eddard%20Stark"
+                    .to_string(),
+            ),
+        )
+    } else if let Some(invalid_body) = rejection.find::<warp::body::BodyDeserializeError>() {
+        ApiError::new(
+            ErrorCode::InvalidBody,
+            invalid_body.to_string(),
+            Some("The body must be JSON, see the README for the expected fields".to_string()),
+        )
+    } else if rejection.find::<warp::reject::PayloadTooLarge>().is_some()
+        || rejection.find::<warp::reject::LengthRequired>().is_some()
+    {
+        ApiError::new(
+            ErrorCode::InvalidBody,
+            "The body is missing or too large",
+            None,
+        )
+    } else if rejection.find::<warp::reject::MethodNotAllowed>().is_some() {
+        ApiError::new(
+            ErrorCode::MethodNotAllowed,
+            "The route does not accept this HTTP method",
+            None,
+        )
+    } else {
+        log::error!("Unhandled rejection {:?}", rejection);
+        ApiError::new(ErrorCode::Internal, "Internal server error", None)
+    };
+    Ok(error)
+}
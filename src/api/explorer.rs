@@ -0,0 +1,23 @@
+//! Family explorer page, searching people and showing their relatives, heir and family tree
+//! through the JSON API. It is bundled in the binary and loads nothing else, so it works offline.
+use super::openapi::{Endpoint, Response};
+use warp::Filter;
+
+const PAGE: &str = include_str!("explorer.html");
+
+/// GET / => 200 OK with the explorer page
+pub fn explorer_route(
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path::end()
+        .and(warp::get())
+        .map(|| warp::reply::html(PAGE))
+}
+
+pub fn endpoints() -> Vec<Endpoint> {
+    vec![Endpoint::new(
+        "get",
+        "/",
+        "Page to search people, see their relatives, heir and family tree, and kill or revive them",
+    )
+    .response(Response::text_as(200, "The page", "text/html"))]
+}
@@ -0,0 +1,266 @@
+//! Graph of the people around a person, as Graphviz DOT, SVG, JSON or a text tree of its
+//! descendants, and SVG drawing of the whole lineage
+use super::error::ApiError;
+use super::model::{GraphLink, GraphResponse, PersonSummary};
+use super::openapi::{Endpoint, Response};
+use crate::lineage::{GraphOptions, Lineage, Person};
+use async_std::sync::{Arc, RwLock};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::convert::Infallible;
+use warp::http::header::CONTENT_TYPE;
+use warp::{Filter, Reply};
+
+#[derive(Debug, Clone, Copy, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphFormat {
+    Dot,
+    Svg,
+    Json,
+    Text,
+}
+
+/// Query of GET /graph
+#[derive(Debug, Deserialize)]
+pub struct GraphQuery {
+    name: String,
+    #[serde(default = "default_generations")]
+    up: usize,
+    #[serde(default = "default_generations")]
+    down: usize,
+    #[serde(default)]
+    siblings: bool,
+    #[serde(default = "default_format")]
+    format: GraphFormat,
+}
+
+fn default_generations() -> usize {
+    2
+}
+
+fn default_format() -> GraphFormat {
+    GraphFormat::Dot
+}
+
+/// GET /graph?name={name}&up={n}&down={n}&siblings={bool}&format={dot|svg|json|text} => 200 OK with
+/// the graph of the people around the person, its heir highlighted
+pub fn get_graph_route(
+    lineage_ref: Arc<RwLock<Lineage>>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("graph")
+        .and(warp::get())
+        .and(warp::query::<GraphQuery>())
+        .and(warp::any().map(move || lineage_ref.clone()))
+        .and_then(get_graph)
+}
+
+/// Query of GET /graph.svg, the whole lineage being drawn without a name
+#[derive(Debug, Deserialize)]
+pub struct GraphSvgQuery {
+    name: Option<String>,
+    #[serde(default = "default_generations")]
+    up: usize,
+    #[serde(default = "default_generations")]
+    down: usize,
+    #[serde(default)]
+    siblings: bool,
+}
+
+/// GET /graph.svg?name={name}&up={n}&down={n}&siblings={bool} => 200 OK with the SVG drawing of
+/// the people around the person, or of the whole lineage without a name
+pub fn get_graph_svg_route(
+    lineage_ref: Arc<RwLock<Lineage>>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("graph.svg")
+        .and(warp::get())
+        .and(warp::query::<GraphSvgQuery>())
+        .and(warp::any().map(move || lineage_ref.clone()))
+        .and_then(get_graph_svg)
+}
+
+async fn get_graph(
+    query: GraphQuery,
+    lineage: Arc<RwLock<Lineage>>,
+) -> Result<impl warp::Reply, Infallible> {
+    let lineage = lineage.read().await;
+    let person = match lineage.resolve(&query.name) {
+        Ok(person) => person,
+        Err(e) => return Ok(ApiError::unresolved(&lineage, e).into_response()),
+    };
+    let (people, heir, heir_path) =
+        surroundings(&lineage, person, query.up, query.down, query.siblings);
+    let options = graph_options(person, &people);
+    Ok(match query.format {
+        GraphFormat::Json => {
+            warp::reply::json(&graph_response(person, &people, heir, &heir_path)).into_response()
+        }
+        GraphFormat::Svg => svg_reply(lineage.to_svg(&options)),
+        GraphFormat::Text => warp::reply::with_header(
+            lineage.to_text_tree(person, Some(query.down)),
+            CONTENT_TYPE,
+            "text/plain; charset=utf-8",
+        )
+        .into_response(),
+        GraphFormat::Dot => warp::reply::with_header(
+            lineage.to_graphviz_with(&options),
+            CONTENT_TYPE,
+            "text/vnd.graphviz",
+        )
+        .into_response(),
+    })
+}
+
+async fn get_graph_svg(
+    query: GraphSvgQuery,
+    lineage: Arc<RwLock<Lineage>>,
+) -> Result<impl warp::Reply, Infallible> {
+    let lineage = lineage.read().await;
+    let name = match &query.name {
+        Some(name) => name,
+        None => {
+            return Ok(svg_reply(lineage.to_svg(&GraphOptions {
+                house_clusters: true,
+                grey_out_dead: true,
+                ..GraphOptions::default()
+            })))
+        }
+    };
+    let person = match lineage.resolve(name) {
+        Ok(person) => person,
+        Err(e) => return Ok(ApiError::unresolved(&lineage, e).into_response()),
+    };
+    let (people, _, _) = surroundings(&lineage, person, query.up, query.down, query.siblings);
+    Ok(svg_reply(lineage.to_svg(&graph_options(person, &people))))
+}
+
+fn svg_reply(svg: String) -> warp::reply::Response {
+    warp::reply::with_header(svg, CONTENT_TYPE, "image/svg+xml").into_response()
+}
+
+/// The people drawn around the person, in insertion order, its heir and the path to the heir,
+/// whose people are drawn too. The path is empty when the heir is not a blood relative, as an heir
+/// of the house tier can be.
+fn surroundings<'a>(
+    lineage: &'a Lineage,
+    person: &'a Person,
+    up: usize,
+    down: usize,
+    siblings: bool,
+) -> (Vec<&'a Person>, Option<&'a Person>, Vec<&'a Person>) {
+    let mut people = lineage.neighbourhood(person, up, down, siblings);
+    let heir = lineage
+        .succession_of(person, lineage.succession_law())
+        .map(|succession| succession.successor);
+    let heir_path = heir
+        .and_then(|heir| lineage.kinship_path(person, heir))
+        .unwrap_or_default();
+    people.extend(heir.into_iter().chain(heir_path.iter().cloned()));
+    people.sort_by_key(|person| person.idx());
+    people.dedup();
+    (people, heir, heir_path)
+}
+
+fn graph_options<'a>(person: &'a Person, people: &[&Person]) -> GraphOptions<'a> {
+    GraphOptions {
+        house_clusters: true,
+        grey_out_dead: true,
+        couple_nodes: true,
+        heir_of: Some(person),
+        only: Some(people.iter().map(|person| person.idx()).collect()),
+        ..GraphOptions::default()
+    }
+}
+
+fn graph_response(
+    person: &Person,
+    people: &[&Person],
+    heir: Option<&Person>,
+    heir_path: &[&Person],
+) -> GraphResponse {
+    let mut links = vec![];
+    for child in people {
+        for parent in child.father_idx().into_iter().chain(child.mother_idx()) {
+            if let Some(parent) = people.iter().find(|person| person.idx() == parent) {
+                links.push(GraphLink {
+                    parent: parent.id().to_string(),
+                    child: child.id().to_string(),
+                });
+            }
+        }
+    }
+    GraphResponse {
+        person: PersonSummary::new(person),
+        people: people
+            .iter()
+            .map(|person| PersonSummary::new(person))
+            .collect(),
+        links,
+        heir: heir.map(PersonSummary::new),
+        heir_path: heir_path
+            .iter()
+            .map(|person| person.id().to_string())
+            .collect(),
+    }
+}
+
+pub fn endpoints() -> Vec<Endpoint> {
+    vec![
+        Endpoint::new(
+            "get",
+            "/graph",
+            "Graph of the ancestors and descendants of a person, its heir highlighted",
+        )
+        .query::<String>("name", true, "Id or name of the person", "Son House")
+        .query::<usize>("up", false, "Generations of ancestors, 2 by default", "1")
+        .query::<usize>(
+            "down",
+            false,
+            "Generations of descendants, 2 by default",
+            "1",
+        )
+        .query::<bool>(
+            "siblings",
+            false,
+            "Also draws the brothers and sisters",
+            "true",
+        )
+        .query::<GraphFormat>(
+            "format",
+            false,
+            "dot (the default), svg, json or text, the tree of the descendants",
+            "dot",
+        )
+        .response(
+            Response::json::<GraphResponse>(200, "The graph, in the requested format")
+                .or_text("text/vnd.graphviz")
+                .or_text("image/svg+xml")
+                .or_text("text/plain"),
+        )
+        .response(Response::error(400, "INVALID_QUERY"))
+        .response(Response::error(404, "PERSON_NOT_FOUND"))
+        .response(Response::error(409, "AMBIGUOUS_NAME")),
+        Endpoint::new(
+            "get",
+            "/graph.svg",
+            "SVG drawing of the people around a person, or of the whole lineage without a name",
+        )
+        .query::<String>("name", false, "Id or name of the person", "Son House")
+        .query::<usize>("up", false, "Generations of ancestors, 2 by default", "1")
+        .query::<usize>(
+            "down",
+            false,
+            "Generations of descendants, 2 by default",
+            "1",
+        )
+        .query::<bool>(
+            "siblings",
+            false,
+            "Also draws the brothers and sisters",
+            "true",
+        )
+        .response(Response::text_as(200, "The drawing", "image/svg+xml"))
+        .response(Response::error(400, "INVALID_QUERY"))
+        .response(Response::error(404, "PERSON_NOT_FOUND"))
+        .response(Response::error(409, "AMBIGUOUS_NAME")),
+    ]
+}
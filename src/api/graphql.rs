@@ -0,0 +1,412 @@
+//! GraphQL schema over the lineage, served at POST /graphql with a GraphiQL page at GET /graphiql.
+//! People are resolved lazily by index, so nested relatives are only looked up when selected.
+use super::changes::ChangeLog;
+use super::error::{ApiError, ErrorCode};
+use super::json_body;
+use super::openapi::{Endpoint, Response};
+use crate::lineage::{KillError, Lineage, Person, ReviveError, Sex, SuccessionChange};
+use async_lock::{RwLockReadGuardArc, RwLockWriteGuardArc};
+use async_std::sync::{Arc, RwLock};
+use juniper::http::GraphQLRequest;
+use juniper::{FieldError, FieldResult, InputValue, RootNode};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::convert::Infallible;
+use warp::http::StatusCode;
+use warp::Filter;
+
+pub type Schema = RootNode<'static, Query, Mutation>;
+
+pub fn schema() -> Schema {
+    Schema::new(Query, Mutation)
+}
+
+/// The lineage locked for the whole request, for writing only if it has a mutation
+enum LineageGuard {
+    Read(RwLockReadGuardArc<Lineage>),
+    Write(RwLockWriteGuardArc<Lineage>),
+}
+
+pub struct Context {
+    lineage: RefCell<LineageGuard>,
+    changes: Arc<ChangeLog>,
+}
+
+impl juniper::Context for Context {}
+
+impl Context {
+    fn read<T>(&self, f: impl FnOnce(&Lineage) -> T) -> T {
+        match &*self.lineage.borrow() {
+            LineageGuard::Read(lineage) => f(lineage),
+            LineageGuard::Write(lineage) => f(lineage),
+        }
+    }
+
+    fn write<T>(
+        &self,
+        f: impl FnOnce(&mut Lineage, &ChangeLog) -> FieldResult<T>,
+    ) -> FieldResult<T> {
+        match &mut *self.lineage.borrow_mut() {
+            LineageGuard::Write(lineage) => f(lineage, &self.changes),
+            // requests with a mutation always lock for writing
+            LineageGuard::Read(_) => Err(FieldError::new(
+                "The lineage was not locked for writing",
+                juniper::Value::null(),
+            )),
+        }
+    }
+
+    /// Runs `f` on the person at `idx`, which was looked up in the same request
+    fn person<T>(&self, idx: usize, f: impl FnOnce(&Lineage, &Person) -> T) -> T {
+        self.read(|lineage| {
+            let person = lineage.get_from_idx(idx).expect("Invalid person idx");
+            f(lineage, person)
+        })
+    }
+}
+
+impl From<ApiError> for FieldError {
+    fn from(error: ApiError) -> Self {
+        let mut extensions = juniper::Object::with_capacity(4);
+        extensions.add_field("code", juniper::Value::scalar(error.code.name()));
+        if let Some(suggestion) = error.suggestion {
+            extensions.add_field("suggestion", juniper::Value::scalar(suggestion));
+        }
+        if !error.did_you_mean.is_empty() {
+            let names = error.did_you_mean.into_iter().map(juniper::Value::scalar);
+            extensions.add_field("didYouMean", juniper::Value::list(names.collect()));
+        }
+        if !error.candidates.is_empty() {
+            let ids = error
+                .candidates
+                .into_iter()
+                .map(|candidate| juniper::Value::scalar(candidate.id));
+            extensions.add_field("candidates", juniper::Value::list(ids.collect()));
+        }
+        FieldError::new(error.message, juniper::Value::Object(extensions))
+    }
+}
+
+#[derive(juniper::GraphQLEnum)]
+#[graphql(name = "Sex")]
+pub enum SexValue {
+    Male,
+    Female,
+}
+
+impl From<Sex> for SexValue {
+    fn from(sex: Sex) -> Self {
+        match sex {
+            Sex::Male => SexValue::Male,
+            Sex::Female => SexValue::Female,
+        }
+    }
+}
+
+/// A person of the lineage, referred to by its index
+pub struct PersonNode {
+    idx: usize,
+}
+
+impl PersonNode {
+    fn new(person: &Person) -> Self {
+        PersonNode { idx: person.idx() }
+    }
+
+    /// Sorted alphabetically, the accessors do not guarantee any ordering
+    fn list(mut people: Vec<&Person>) -> Vec<PersonNode> {
+        people.sort();
+        people.into_iter().map(PersonNode::new).collect()
+    }
+}
+
+#[juniper::object(name = "Person", Context = Context)]
+impl PersonNode {
+    /// Unique, unlike the name
+    fn id(&self, context: &Context) -> String {
+        context.person(self.idx, |_, person| person.id().to_string())
+    }
+
+    fn name(&self, context: &Context) -> String {
+        context.person(self.idx, |_, person| person.name().to_string())
+    }
+
+    fn house(&self, context: &Context) -> String {
+        context.person(self.idx, |_, person| person.house().to_string())
+    }
+
+    fn sex(&self, context: &Context) -> SexValue {
+        context.person(self.idx, |_, person| person.sex().into())
+    }
+
+    fn alive(&self, context: &Context) -> bool {
+        context.person(self.idx, |_, person| person.alive())
+    }
+
+    fn father(&self, context: &Context) -> Option<PersonNode> {
+        context.person(self.idx, |lineage, person| {
+            lineage.get_father_of(person).map(PersonNode::new)
+        })
+    }
+
+    fn mother(&self, context: &Context) -> Option<PersonNode> {
+        context.person(self.idx, |lineage, person| {
+            lineage.get_mother_of(person).map(PersonNode::new)
+        })
+    }
+
+    fn sons(&self, context: &Context) -> Vec<PersonNode> {
+        context.person(self.idx, |lineage, person| {
+            PersonNode::list(lineage.get_sons_of(person))
+        })
+    }
+
+    fn daughters(&self, context: &Context) -> Vec<PersonNode> {
+        context.person(self.idx, |lineage, person| {
+            PersonNode::list(lineage.get_daughters_of(person))
+        })
+    }
+
+    fn brothers(&self, context: &Context) -> Vec<PersonNode> {
+        context.person(self.idx, |lineage, person| {
+            PersonNode::list(lineage.get_brothers(person))
+        })
+    }
+
+    fn sisters(&self, context: &Context) -> Vec<PersonNode> {
+        context.person(self.idx, |lineage, person| {
+            PersonNode::list(lineage.get_sisters(person))
+        })
+    }
+
+    fn nephews(&self, context: &Context) -> Vec<PersonNode> {
+        context.person(self.idx, |lineage, person| {
+            PersonNode::list(lineage.get_nephews(person))
+        })
+    }
+
+    fn nieces(&self, context: &Context) -> Vec<PersonNode> {
+        context.person(self.idx, |lineage, person| {
+            PersonNode::list(lineage.get_nieces(person))
+        })
+    }
+
+    /// Heir under the succession law of the server, null when nobody alive can inherit
+    fn successor(&self, context: &Context) -> Option<PersonNode> {
+        context.person(self.idx, |lineage, person| {
+            lineage
+                .succession_of(person, lineage.succession_law())
+                .map(|succession| PersonNode::new(succession.successor))
+        })
+    }
+}
+
+#[juniper::object(Context = Context)]
+impl SuccessionChange {
+    fn person(&self) -> &str {
+        &self.person
+    }
+
+    fn old_successor(&self) -> Option<&str> {
+        self.old_successor.as_deref()
+    }
+
+    fn new_successor(&self) -> Option<&str> {
+        self.new_successor.as_deref()
+    }
+}
+
+/// Result of the kill and revive mutations
+pub struct MutationResult {
+    person: PersonNode,
+    succession_changes: Vec<SuccessionChange>,
+}
+
+#[juniper::object(Context = Context)]
+impl MutationResult {
+    fn person(&self) -> &PersonNode {
+        &self.person
+    }
+
+    /// The living people whose heir changed, ordered by name
+    fn succession_changes(&self) -> &[SuccessionChange] {
+        &self.succession_changes
+    }
+}
+
+pub struct Query;
+
+#[juniper::object(Context = Context)]
+impl Query {
+    /// The person with the id or the name, null if there is nobody or several people with it
+    fn person(context: &Context, name: String) -> Option<PersonNode> {
+        context.read(|lineage| lineage.resolve(&name).ok().map(PersonNode::new))
+    }
+
+    /// Everybody matching the given filters, ordered alphabetically
+    fn people(context: &Context, house: Option<String>, alive: Option<bool>) -> Vec<PersonNode> {
+        context.read(|lineage| {
+            PersonNode::list(
+                lineage
+                    .people()
+                    .iter()
+                    .filter(|person| {
+                        house.as_deref().is_none_or(|house| person.house() == house)
+                            && alive.is_none_or(|alive| person.alive() == alive)
+                    })
+                    .collect(),
+            )
+        })
+    }
+}
+
+pub struct Mutation;
+
+/// Id and name of the person `key` refers to
+fn resolve(lineage: &Lineage, key: &str) -> Result<(String, String), ApiError> {
+    lineage
+        .resolve(key)
+        .map(|person| (person.id().to_string(), person.name().to_string()))
+        .map_err(|e| ApiError::unresolved(lineage, e))
+}
+
+#[juniper::object(Context = Context)]
+impl Mutation {
+    fn kill(context: &Context, name: String) -> FieldResult<MutationResult> {
+        context.write(|lineage, changes| {
+            let (id, name) = resolve(lineage, &name)?;
+            let succession_changes = changes.kill(lineage, &id).map_err(|e| match e {
+                KillError::PersonNotFound => ApiError::person_not_found(&name),
+                KillError::PersonAlreadyDead => ApiError::already_dead(&name),
+            })?;
+            Ok(MutationResult {
+                person: PersonNode::new(lineage.get_from_id(&id).expect("killed person")),
+                succession_changes,
+            })
+        })
+    }
+
+    fn revive(context: &Context, name: String) -> FieldResult<MutationResult> {
+        context.write(|lineage, changes| {
+            let (id, name) = resolve(lineage, &name)?;
+            let succession_changes = changes.revive(lineage, &id).map_err(|e| match e {
+                ReviveError::PersonNotFound => ApiError::person_not_found(&name),
+                ReviveError::PersonAlive => {
+                    ApiError::new(ErrorCode::AlreadyAlive, format!("{} is alive", name), None)
+                }
+            })?;
+            Ok(MutationResult {
+                person: PersonNode::new(lineage.get_from_id(&id).expect("revived person")),
+                succession_changes,
+            })
+        })
+    }
+}
+
+/// Body of POST /graphql, as sent by GraphQL clients
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GraphQLBody {
+    query: String,
+    #[serde(rename = "operationName")]
+    operation_name: Option<String>,
+    #[schemars(with = "Option<serde_json::Map<String, serde_json::Value>>")]
+    variables: Option<InputValue>,
+}
+
+/// True if the document has a mutation operation, looking for the keyword outside of any
+/// selection set. Strings in default values could give false positives, which only cost locking
+/// for writing.
+fn has_mutation(query: &str) -> bool {
+    let mut depth = 0usize;
+    let mut outside_selections = String::new();
+    for line in query.lines() {
+        // comments run until the end of the line
+        for c in line.split('#').next().unwrap_or("").chars() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth = depth.saturating_sub(1),
+                c if depth == 0 => outside_selections.push(c),
+                _ => {}
+            }
+        }
+        outside_selections.push('\n');
+    }
+    outside_selections
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .any(|word| word == "mutation")
+}
+
+/// POST /graphql with a `GraphQLBody` => 200 OK with the GraphQL response, 400 BAD_REQUEST if the
+/// query could not be executed at all
+pub fn graphql_route(
+    lineage_ref: Arc<RwLock<Lineage>>,
+    changes: Arc<ChangeLog>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    let schema = Arc::new(schema());
+    warp::path!("graphql")
+        .and(warp::post())
+        .and(json_body::<GraphQLBody>())
+        .and(warp::any().map(move || lineage_ref.clone()))
+        .and(warp::any().map(move || changes.clone()))
+        .and(warp::any().map(move || schema.clone()))
+        .and_then(graphql)
+}
+
+async fn graphql(
+    body: GraphQLBody,
+    lineage: Arc<RwLock<Lineage>>,
+    changes: Arc<ChangeLog>,
+    schema: Arc<Schema>,
+) -> Result<impl warp::Reply, Infallible> {
+    let guard = if has_mutation(&body.query) {
+        LineageGuard::Write(lineage.write_arc().await)
+    } else {
+        LineageGuard::Read(lineage.read_arc().await)
+    };
+    let context = Context {
+        lineage: RefCell::new(guard),
+        changes,
+    };
+    let request = GraphQLRequest::new(body.query, body.operation_name, body.variables);
+    let response = request.execute(&schema, &context);
+    let status = if response.is_ok() {
+        StatusCode::OK
+    } else {
+        StatusCode::BAD_REQUEST
+    };
+    Ok(warp::reply::with_status(
+        warp::reply::json(&response),
+        status,
+    ))
+}
+
+/// GET /graphiql => 200 OK with a GraphiQL page to explore the schema
+pub fn graphiql_route(
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("graphiql")
+        .and(warp::get())
+        .map(|| warp::reply::html(juniper::graphiql::graphiql_source("/graphql")))
+}
+
+pub fn endpoints() -> Vec<Endpoint> {
+    vec![
+        Endpoint::new("post", "/graphql", "Executes a GraphQL query or mutation")
+            .json_body::<GraphQLBody>()
+            .response(Response::json::<serde_json::Value>(
+                200,
+                "The GraphQL response",
+            ))
+            .response(Response::json::<serde_json::Value>(
+                400,
+                "The GraphQL response, the query could not be executed",
+            )),
+        Endpoint::new("get", "/graphiql", "GraphiQL page to explore the schema").response(
+            Response {
+                status: 200,
+                description: "The page",
+                content: vec![],
+            },
+        ),
+    ]
+}
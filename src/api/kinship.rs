@@ -0,0 +1,65 @@
+use super::error::ApiError;
+use super::model::{BloodRelationship, KinshipResponse, PersonSummary};
+use super::openapi::{Endpoint, Response};
+use crate::lineage::Lineage;
+use async_std::sync::{Arc, RwLock};
+use serde::Deserialize;
+use std::convert::Infallible;
+use warp::{Filter, Reply};
+
+/// Query of GET /relationship, ids or names
+#[derive(Debug, Deserialize)]
+pub struct PairQuery {
+    a: String,
+    b: String,
+}
+
+/// GET /relationship?a={name}&b={name} => 200 OK with how b is related to a
+pub fn get_relationship_route(
+    lineage_ref: Arc<RwLock<Lineage>>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("relationship")
+        .and(warp::get())
+        .and(warp::query::<PairQuery>())
+        .and(warp::any().map(move || lineage_ref.clone()))
+        .and_then(get_relationship)
+}
+
+async fn get_relationship(
+    query: PairQuery,
+    lineage: Arc<RwLock<Lineage>>,
+) -> Result<impl warp::Reply, Infallible> {
+    let lineage = lineage.read().await;
+    let (a, b) = match (lineage.resolve(&query.a), lineage.resolve(&query.b)) {
+        (Ok(a), Ok(b)) => (a, b),
+        (Err(e), _) | (_, Err(e)) => return Ok(ApiError::unresolved(&lineage, e).into_response()),
+    };
+    let relationship = lineage.relationship(a, b);
+    Ok(warp::reply::json(&KinshipResponse {
+        a: PersonSummary::new(a),
+        b: PersonSummary::new(b),
+        relationship: relationship.as_ref().map(BloodRelationship::new),
+    })
+    .into_response())
+}
+
+pub fn endpoints() -> Vec<Endpoint> {
+    vec![Endpoint::new(
+        "get",
+        "/relationship",
+        "How b is related to a by blood, through their closest common ancestors",
+    )
+    .query::<String>("a", true, "Id or name of the first person", "Son House")
+    .query::<String>(
+        "b",
+        true,
+        "Id or name of the second person",
+        "Grandson Other",
+    )
+    .response(Response::json::<KinshipResponse>(
+        200,
+        "The relationship, null if they have no common ancestor",
+    ))
+    .response(Response::error(404, "PERSON_NOT_FOUND"))
+    .response(Response::error(409, "AMBIGUOUS_NAME"))]
+}
@@ -0,0 +1,178 @@
+//! HTTP API. `v1` routes answer with plain text and are kept for old clients, `v2` routes live
+//! under the `/v2` prefix and answer with JSON, as do the `people` resource routes. `graphql`
+//! serves the same data for clients fetching nested relatives in one request, and `explorer` a
+//! page using the JSON routes for people who would rather click than curl.
+mod batch;
+mod changes;
+mod error;
+mod explorer;
+mod graph;
+mod graphql;
+mod kinship;
+mod model;
+mod openapi;
+mod people;
+mod relationships;
+mod stream;
+mod v1;
+mod v2;
+
+pub use changes::ChangeLog;
+
+use crate::lineage::Lineage;
+use async_std::sync::{Arc, RwLock};
+use serde::Deserialize;
+use std::convert::Infallible;
+use warp::filters::BoxedFilter;
+use warp::{Filter, Reply};
+
+#[cfg(test)]
+mod tests;
+
+/// Represents the name query parameter in the request
+#[derive(Deserialize)]
+struct NameQueryParam {
+    name: String,
+}
+
+/// A route filter with the answer of whichever handler matched
+type BoxedRoute = BoxedFilter<(warp::reply::Response,)>;
+
+fn boxed<F, R>(route: F) -> BoxedRoute
+where
+    F: Filter<Extract = (R,), Error = warp::Rejection> + Clone + Send + Sync + 'static,
+    R: Reply,
+{
+    route.map(|reply: R| reply.into_response()).boxed()
+}
+
+/// Every route with the method and the path of the endpoint documenting it in
+/// `openapi::endpoints`, tried in this order. The tests check the table and the document list the
+/// same endpoints, and that each route answers the example request of its endpoint.
+fn route_table(
+    lineage: Arc<RwLock<Lineage>>,
+    changes: Arc<ChangeLog>,
+) -> Vec<(&'static str, &'static str, BoxedRoute)> {
+    // each route needs a handle to the lineage in order to query or modify it
+    vec![
+        (
+            "get",
+            "/successor",
+            boxed(v1::get_successor_route(lineage.clone())),
+        ),
+        (
+            "post",
+            "/kill",
+            boxed(v1::kill_person_route(lineage.clone(), changes.clone())),
+        ),
+        (
+            "post",
+            "/kill/batch",
+            boxed(batch::kill_batch_route(lineage.clone(), changes.clone())),
+        ),
+        (
+            "get",
+            "/v2/successor",
+            boxed(warp::path("v2").and(v2::get_successor_route(lineage.clone()))),
+        ),
+        (
+            "post",
+            "/v2/kill",
+            boxed(warp::path("v2").and(v2::kill_person_route(lineage.clone(), changes.clone()))),
+        ),
+        (
+            "get",
+            "/people",
+            boxed(people::list_people_route(lineage.clone())),
+        ),
+        (
+            "post",
+            "/people",
+            boxed(people::add_person_route(lineage.clone(), changes.clone())),
+        ),
+        (
+            "get",
+            "/people/{name}",
+            boxed(people::get_person_route(lineage.clone())),
+        ),
+        (
+            "get",
+            "/people/{name}/relatives",
+            boxed(people::get_relatives_route(lineage.clone())),
+        ),
+        (
+            "get",
+            "/people/{name}/ancestors",
+            boxed(people::get_ancestors_route(lineage.clone())),
+        ),
+        (
+            "get",
+            "/people/{name}/descendants",
+            boxed(people::get_descendants_route(lineage.clone())),
+        ),
+        (
+            "post",
+            "/relationships",
+            boxed(relationships::add_relationship_route(
+                lineage.clone(),
+                changes.clone(),
+            )),
+        ),
+        (
+            "delete",
+            "/relationships",
+            boxed(relationships::remove_relationship_route(
+                lineage.clone(),
+                changes.clone(),
+            )),
+        ),
+        (
+            "get",
+            "/relationship",
+            boxed(kinship::get_relationship_route(lineage.clone())),
+        ),
+        (
+            "get",
+            "/graph",
+            boxed(graph::get_graph_route(lineage.clone())),
+        ),
+        (
+            "get",
+            "/graph.svg",
+            boxed(graph::get_graph_svg_route(lineage.clone())),
+        ),
+        (
+            "get",
+            "/successions/stream",
+            boxed(stream::successions_stream_route(changes.clone())),
+        ),
+        (
+            "post",
+            "/graphql",
+            boxed(graphql::graphql_route(lineage, changes)),
+        ),
+        ("get", "/graphiql", boxed(graphql::graphiql_route())),
+        ("get", "/openapi.json", boxed(openapi::openapi_route())),
+        ("get", "/", boxed(explorer::explorer_route())),
+    ]
+}
+
+/// All the routes served by the application, rejections are turned into JSON errors
+pub fn routes(
+    lineage: Arc<RwLock<Lineage>>,
+    changes: Arc<ChangeLog>,
+) -> impl Filter<Extract = impl warp::Reply, Error = Infallible> + Clone {
+    let mut table = route_table(lineage, changes)
+        .into_iter()
+        .map(|(_, _, route)| route);
+    let first = table.next().expect("there are routes");
+    table
+        .fold(first, |routes, route| routes.or(route).unify().boxed())
+        .recover(error::handle_rejection)
+}
+
+/// JSON bodies are small, anything bigger is rejected before being read
+fn json_body<T: serde::de::DeserializeOwned + Send>(
+) -> impl Filter<Extract = (T,), Error = warp::Rejection> + Clone {
+    warp::body::content_length_limit(16 * 1024).and(warp::body::json())
+}
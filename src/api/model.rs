@@ -0,0 +1,264 @@
+//! JSON representations of the lineage returned by the `v2` routes. Relatives are referred to by
+//! name since graph indices mean nothing outside the process, the records of people have their
+//! id to tell apart people with the same name.
+use crate::lineage::{
+    Lineage, Person, Relationship, Sex, SiblingKind, Succession, SuccessionChanges, SuccessionLaw,
+    SuccessionTier,
+};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Full record of a person
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PersonView {
+    pub id: String,
+    pub name: String,
+    pub house: String,
+    pub sex: Sex,
+    pub alive: bool,
+    pub father: Option<String>,
+    pub mother: Option<String>,
+    pub sons: Vec<String>,
+    pub daughters: Vec<String>,
+}
+
+impl PersonView {
+    pub fn new(lineage: &Lineage, person: &Person) -> Self {
+        let names = |people: Vec<&Person>| people.iter().map(|p| p.name().to_string()).collect();
+        PersonView {
+            id: person.id().to_string(),
+            name: person.name().to_string(),
+            house: person.house().to_string(),
+            sex: person.sex(),
+            alive: person.alive(),
+            father: lineage.get_father_of(person).map(|p| p.name().to_string()),
+            mother: lineage.get_mother_of(person).map(|p| p.name().to_string()),
+            sons: names(lineage.get_sons_of(person)),
+            daughters: names(lineage.get_daughters_of(person)),
+        }
+    }
+}
+
+/// Short record of a person, used when listing relatives
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PersonSummary {
+    pub id: String,
+    pub name: String,
+    pub house: String,
+    pub sex: Sex,
+    pub alive: bool,
+}
+
+impl PersonSummary {
+    pub fn new(person: &Person) -> Self {
+        PersonSummary {
+            id: person.id().to_string(),
+            name: person.name().to_string(),
+            house: person.house().to_string(),
+            sex: person.sex(),
+            alive: person.alive(),
+        }
+    }
+}
+
+/// Body of GET /people/{name}/relatives, lists are ordered alphabetically
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RelativesResponse {
+    pub person: PersonSummary,
+    pub father: Option<PersonSummary>,
+    pub mother: Option<PersonSummary>,
+    pub sons: Vec<PersonSummary>,
+    pub daughters: Vec<PersonSummary>,
+    pub brothers: Vec<PersonSummary>,
+    pub sisters: Vec<PersonSummary>,
+    pub nephews: Vec<PersonSummary>,
+    pub nieces: Vec<PersonSummary>,
+    pub grandparents: Vec<PersonSummary>,
+    pub grandchildren: Vec<PersonSummary>,
+    pub uncles: Vec<PersonSummary>,
+    pub aunts: Vec<PersonSummary>,
+    /// First cousins
+    pub cousins: Vec<PersonSummary>,
+    /// Brothers and sisters with the parents they share with the person
+    pub siblings: Vec<SiblingSummary>,
+}
+
+/// A brother or a sister, `kind` telling whether both parents are shared
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SiblingSummary {
+    pub kind: SiblingKind,
+    pub person: PersonSummary,
+}
+
+/// An ancestor or a descendant, `generation` generations away from the person
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GenerationMember {
+    pub generation: usize,
+    pub person: PersonSummary,
+}
+
+impl GenerationMember {
+    pub fn new(person: &Person, generation: usize) -> Self {
+        GenerationMember {
+            generation,
+            person: PersonSummary::new(person),
+        }
+    }
+}
+
+/// A parent/child link, by ids
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GraphLink {
+    pub parent: String,
+    pub child: String,
+}
+
+/// Body of GET /graph?format=json, the people around a person and the links between them
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GraphResponse {
+    pub person: PersonSummary,
+    /// In insertion order, with the heir and the people on the way to it
+    pub people: Vec<PersonSummary>,
+    pub links: Vec<GraphLink>,
+    pub heir: Option<PersonSummary>,
+    /// Ids from the person to its heir, empty without an heir or when the heir is not a blood
+    /// relative
+    pub heir_path: Vec<String>,
+}
+
+/// Body of POST and DELETE /relationships, the records after the change
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RelationshipResponse {
+    pub parent: PersonView,
+    pub child: PersonView,
+}
+
+/// How a person in a kinship path relates to the previous one
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Kinship {
+    Father,
+    Mother,
+    Son,
+    Daughter,
+}
+
+/// One step of the chain of parent/child links between two people
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct KinshipStep {
+    pub name: String,
+    /// None for the first person of the path
+    pub relation: Option<Kinship>,
+}
+
+impl KinshipStep {
+    pub fn path(path: &[&Person]) -> Vec<KinshipStep> {
+        let mut steps = Vec::with_capacity(path.len());
+        for (i, person) in path.iter().enumerate() {
+            let relation = if i == 0 {
+                None
+            } else {
+                let previous = path[i - 1];
+                Some(if previous.father_idx() == Some(person.idx()) {
+                    Kinship::Father
+                } else if previous.mother_idx() == Some(person.idx()) {
+                    Kinship::Mother
+                } else if previous.sons_idx().contains(&person.idx()) {
+                    Kinship::Son
+                } else {
+                    Kinship::Daughter
+                })
+            };
+            steps.push(KinshipStep {
+                name: person.name().to_string(),
+                relation,
+            });
+        }
+        steps
+    }
+}
+
+/// Body of GET /v2/successor. When nobody alive can inherit `successor` and `tier` are null and
+/// `kinship_path` is empty.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SuccessorResponse {
+    pub person: PersonView,
+    pub law: SuccessionLaw,
+    pub successor: Option<PersonView>,
+    pub tier: Option<SuccessionTier>,
+    /// From the person to its successor, both included
+    pub kinship_path: Vec<KinshipStep>,
+}
+
+impl SuccessorResponse {
+    pub fn new(
+        lineage: &Lineage,
+        person: &Person,
+        law: SuccessionLaw,
+        succession: Option<Succession>,
+    ) -> Self {
+        let kinship_path = succession
+            .as_ref()
+            .and_then(|succession| lineage.kinship_path(person, succession.successor))
+            .map(|path| KinshipStep::path(&path))
+            .unwrap_or_default();
+        SuccessorResponse {
+            person: PersonView::new(lineage, person),
+            law,
+            successor: succession
+                .as_ref()
+                .map(|succession| PersonView::new(lineage, succession.successor)),
+            tier: succession.map(|succession| succession.tier),
+            kinship_path,
+        }
+    }
+}
+
+/// Body of POST /v2/kill
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct KillResponse {
+    pub killed: PersonView,
+    /// The living people whose heir changed, ordered by name
+    pub succession_changes: SuccessionChanges,
+}
+
+/// Blood relationship of `b` to `a` in a `KinshipResponse`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BloodRelationship {
+    /// e.g. "first cousin once removed", "great-uncle" or "half-sister"
+    pub name: String,
+    /// Generations from `a` up to the common ancestors
+    pub generations_up: usize,
+    /// Generations from the common ancestors down to `b`
+    pub generations_down: usize,
+    pub half: bool,
+    /// Ordered alphabetically
+    pub common_ancestors: Vec<PersonSummary>,
+    /// From `a` to `b` through one of the common ancestors, both included
+    pub path: Vec<KinshipStep>,
+}
+
+impl BloodRelationship {
+    pub fn new(relationship: &Relationship) -> Self {
+        BloodRelationship {
+            name: relationship.name.clone(),
+            generations_up: relationship.generations_up,
+            generations_down: relationship.generations_down,
+            half: relationship.half,
+            common_ancestors: relationship
+                .common_ancestors
+                .iter()
+                .map(|ancestor| PersonSummary::new(ancestor))
+                .collect(),
+            path: KinshipStep::path(&relationship.path),
+        }
+    }
+}
+
+/// Body of GET /relationship, `relationship` is null when they are not blood relatives
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct KinshipResponse {
+    pub a: PersonSummary,
+    pub b: PersonSummary,
+    pub relationship: Option<BloodRelationship>,
+}
@@ -0,0 +1,261 @@
+//! OpenAPI 3 document describing the routes. Each module lists its endpoints next to the filters
+//! serving them, bodies are described by the schemas derived from the types (de)serialized by the
+//! handlers. `route_table` pairs each filter with the method and path of its endpoint, and the
+//! routes are tested against the document both ways, see `tests::openapi`.
+use super::error::ApiError;
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde_json::{json, Map, Value};
+use std::convert::Infallible;
+use std::sync::Arc;
+use warp::Filter;
+
+/// Gives the schema of a type, registering its definition in the document components
+pub type SchemaFn = fn(&mut SchemaGenerator) -> Schema;
+
+pub fn schema<T: JsonSchema>(generator: &mut SchemaGenerator) -> Schema {
+    generator.subschema_for::<T>()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Location {
+    Query,
+    Path,
+}
+
+#[derive(Debug, Clone)]
+pub struct Parameter {
+    pub name: &'static str,
+    pub location: Location,
+    pub required: bool,
+    pub description: &'static str,
+    /// Valid for the lineage used in the tests
+    pub example: &'static str,
+    pub schema: SchemaFn,
+}
+
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: u16,
+    pub description: &'static str,
+    /// Media types and schemas the body can have, empty if there is no body
+    pub content: Vec<(&'static str, SchemaFn)>,
+}
+
+impl Response {
+    pub fn json<T: JsonSchema>(status: u16, description: &'static str) -> Self {
+        Response {
+            status,
+            description,
+            content: vec![("application/json", schema::<T>)],
+        }
+    }
+
+    pub fn text(status: u16, description: &'static str) -> Self {
+        Response::text_as(status, description, "text/plain")
+    }
+
+    /// A text body of another media type than `text/plain`
+    pub fn text_as(status: u16, description: &'static str, media_type: &'static str) -> Self {
+        Response {
+            status,
+            description,
+            content: vec![(media_type, schema::<String>)],
+        }
+    }
+
+    /// Another media type the body can have, as text
+    pub fn or_text(mut self, media_type: &'static str) -> Self {
+        self.content.push((media_type, schema::<String>));
+        self
+    }
+
+    /// An `ApiError` body
+    pub fn error(status: u16, description: &'static str) -> Self {
+        Response::json::<ApiError>(status, description)
+    }
+}
+
+/// A route, as a method and a path with `{param}` placeholders
+#[derive(Debug, Clone)]
+pub struct Endpoint {
+    pub method: &'static str,
+    pub path: &'static str,
+    pub summary: &'static str,
+    pub parameters: Vec<Parameter>,
+    /// Schema of the JSON body
+    pub request_body: Option<SchemaFn>,
+    pub responses: Vec<Response>,
+}
+
+impl Endpoint {
+    pub fn new(method: &'static str, path: &'static str, summary: &'static str) -> Self {
+        Endpoint {
+            method,
+            path,
+            summary,
+            parameters: vec![],
+            request_body: None,
+            responses: vec![],
+        }
+    }
+
+    pub fn query<T: JsonSchema>(
+        mut self,
+        name: &'static str,
+        required: bool,
+        description: &'static str,
+        example: &'static str,
+    ) -> Self {
+        self.parameters.push(Parameter {
+            name,
+            location: Location::Query,
+            required,
+            description,
+            example,
+            schema: schema::<T>,
+        });
+        self
+    }
+
+    pub fn path_param(
+        mut self,
+        name: &'static str,
+        description: &'static str,
+        example: &'static str,
+    ) -> Self {
+        self.parameters.push(Parameter {
+            name,
+            location: Location::Path,
+            required: true,
+            description,
+            example,
+            schema: schema::<String>,
+        });
+        self
+    }
+
+    pub fn json_body<T: JsonSchema>(mut self) -> Self {
+        self.request_body = Some(schema::<T>);
+        self
+    }
+
+    pub fn response(mut self, response: Response) -> Self {
+        self.responses.push(response);
+        self
+    }
+
+    fn operation(&self, generator: &mut SchemaGenerator) -> Value {
+        let parameters: Vec<Value> = self
+            .parameters
+            .iter()
+            .map(|parameter| {
+                json!({
+                    "name": parameter.name,
+                    "in": match parameter.location {
+                        Location::Query => "query",
+                        Location::Path => "path",
+                    },
+                    "required": parameter.required,
+                    "description": parameter.description,
+                    "example": parameter.example,
+                    "schema": (parameter.schema)(generator),
+                })
+            })
+            .collect();
+        let mut responses = Map::new();
+        for response in &self.responses {
+            let mut body = Map::new();
+            body.insert("description".to_string(), json!(response.description));
+            if !response.content.is_empty() {
+                let content: Map<String, Value> = response
+                    .content
+                    .iter()
+                    .map(|(media_type, schema)| {
+                        (
+                            media_type.to_string(),
+                            json!({ "schema": schema(generator) }),
+                        )
+                    })
+                    .collect();
+                body.insert("content".to_string(), Value::Object(content));
+            }
+            responses.insert(response.status.to_string(), Value::Object(body));
+        }
+        let mut operation = Map::new();
+        operation.insert("summary".to_string(), json!(self.summary));
+        if !parameters.is_empty() {
+            operation.insert("parameters".to_string(), Value::Array(parameters));
+        }
+        if let Some(schema) = self.request_body {
+            operation.insert(
+                "requestBody".to_string(),
+                json!({
+                    "required": true,
+                    "content": { "application/json": { "schema": schema(generator) } },
+                }),
+            );
+        }
+        operation.insert("responses".to_string(), Value::Object(responses));
+        Value::Object(operation)
+    }
+}
+
+/// Every endpoint served by `super::routes`
+pub fn endpoints() -> Vec<Endpoint> {
+    let mut endpoints = vec![];
+    endpoints.extend(super::v1::endpoints());
+    endpoints.extend(super::batch::endpoints());
+    endpoints.extend(super::v2::endpoints());
+    endpoints.extend(super::people::endpoints());
+    endpoints.extend(super::relationships::endpoints());
+    endpoints.extend(super::kinship::endpoints());
+    endpoints.extend(super::graph::endpoints());
+    endpoints.extend(super::stream::endpoints());
+    endpoints.extend(super::graphql::endpoints());
+    endpoints.extend(super::explorer::endpoints());
+    endpoints.push(
+        Endpoint::new("get", "/openapi.json", "This document")
+            .response(Response::json::<Value>(200, "The OpenAPI 3 document")),
+    );
+    endpoints
+}
+
+/// The OpenAPI 3 document of `endpoints`
+pub fn document() -> Value {
+    let mut generator = SchemaSettings::openapi3().into_generator();
+    let mut paths = Map::new();
+    for endpoint in endpoints() {
+        let operation = endpoint.operation(&mut generator);
+        paths
+            .entry(endpoint.path)
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .expect("path items are objects")
+            .insert(endpoint.method.to_string(), operation);
+    }
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "craft ai project",
+            "description": "Line of succession of the families of a lineage",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": paths,
+        "components": { "schemas": generator.definitions() },
+    })
+}
+
+/// GET /openapi.json => 200 OK with the OpenAPI document
+pub fn openapi_route() -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
+{
+    // generated once, the routes do not change while running
+    let document = Arc::new(document());
+    warp::path!("openapi.json")
+        .and(warp::get())
+        .and(warp::any().map(move || document.clone()))
+        .and_then(|document: Arc<Value>| async move {
+            Ok::<_, Infallible>(warp::reply::json(&*document))
+        })
+}
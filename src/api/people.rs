@@ -0,0 +1,380 @@
+use super::changes::ChangeLog;
+use super::error::ApiError;
+use super::json_body;
+use super::model::{
+    GenerationMember, PersonSummary, PersonView, RelativesResponse, SiblingSummary,
+};
+use super::openapi::{Endpoint, Response};
+use crate::lineage::{InsertError, Lineage, Person, PersonFilter, Sex};
+use crate::persistence::Event;
+use async_std::sync::{Arc, RwLock};
+use percent_encoding::percent_decode_str;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::convert::Infallible;
+use warp::http::StatusCode;
+use warp::{Filter, Reply};
+
+/// Optional filters of GET /people, people must match all of the given ones
+#[derive(Debug, Default, Deserialize)]
+pub struct PeopleQuery {
+    house: Option<String>,
+    alive: Option<bool>,
+    sex: Option<Sex>,
+}
+
+impl From<PeopleQuery> for PersonFilter {
+    fn from(query: PeopleQuery) -> Self {
+        PersonFilter {
+            alive: query.alive,
+            sex: query.sex,
+            house: query.house,
+        }
+    }
+}
+
+/// Query of GET /people/{name}/ancestors, all the generations without `max_depth`
+#[derive(Debug, Default, Deserialize)]
+pub struct AncestorsQuery {
+    max_depth: Option<usize>,
+}
+
+/// Query of GET /people/{name}/descendants, the filters are the ones of GET /people
+#[derive(Debug, Default, Deserialize)]
+pub struct DescendantsQuery {
+    max_depth: Option<usize>,
+    house: Option<String>,
+    alive: Option<bool>,
+    sex: Option<Sex>,
+}
+
+/// Body of POST /people, the id is only needed if someone already has the name
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct NewPerson {
+    name: String,
+    sex: Sex,
+    id: Option<String>,
+}
+
+/// Path segments are not decoded by warp, "Eddard%20Stark" needs to become "Eddard Stark"
+fn decode_name(segment: String) -> String {
+    percent_decode_str(&segment)
+        .decode_utf8()
+        .map(|name| name.into_owned())
+        .unwrap_or(segment)
+}
+
+/// GET /people?house={house}&alive={bool}&sex={M|F} => 200 OK with the matching people,
+/// ordered alphabetically
+pub fn list_people_route(
+    lineage_ref: Arc<RwLock<Lineage>>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("people")
+        .and(warp::get())
+        .and(warp::query::<PeopleQuery>())
+        .and(warp::any().map(move || lineage_ref.clone()))
+        .and_then(list_people)
+}
+
+async fn list_people(
+    query: PeopleQuery,
+    lineage: Arc<RwLock<Lineage>>,
+) -> Result<impl warp::Reply, Infallible> {
+    let lineage = lineage.read().await;
+    let filter = PersonFilter::from(query);
+    let mut people: Vec<&Person> = lineage
+        .people()
+        .iter()
+        .filter(|person| filter.matches(person))
+        .collect();
+    people.sort();
+    let people: Vec<PersonView> = people
+        .into_iter()
+        .map(|person| PersonView::new(&lineage, person))
+        .collect();
+    Ok(warp::reply::json(&people))
+}
+
+/// POST /people with a `NewPerson` body => 201 CREATED with the person record
+pub fn add_person_route(
+    lineage_ref: Arc<RwLock<Lineage>>,
+    changes: Arc<ChangeLog>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("people")
+        .and(warp::post())
+        .and(json_body::<NewPerson>())
+        .and(warp::any().map(move || lineage_ref.clone()))
+        .and(warp::any().map(move || changes.clone()))
+        .and_then(add_person)
+}
+
+async fn add_person(
+    new_person: NewPerson,
+    lineage: Arc<RwLock<Lineage>>,
+    changes: Arc<ChangeLog>,
+) -> Result<impl warp::Reply, Infallible> {
+    let mut lineage = lineage.write().await;
+    let added: Result<_, InsertError> = changes.apply(&mut lineage, |lineage| {
+        let changes = lineage.add_person(
+            &new_person.name,
+            new_person.sex.clone(),
+            new_person.id.as_deref(),
+        )?;
+        // without an id the name was not taken, the person is the only one bearing it
+        let id = match &new_person.id {
+            Some(id) => id.clone(),
+            None => lineage
+                .get_from_name(&new_person.name)
+                .expect("added person must exist")
+                .id()
+                .to_string(),
+        };
+        // the assigned id is journaled so it is the same when replaying
+        let event = Event::AddPerson {
+            name: new_person.name.clone(),
+            sex: new_person.sex.clone(),
+            id: Some(id.clone()),
+        };
+        Ok((id, changes, vec![event]))
+    });
+    Ok(match added {
+        Err(e) => ApiError::from(e).into_response(),
+        Ok((id, _)) => {
+            let added = lineage.get_from_id(&id).expect("added person must exist");
+            warp::reply::with_status(
+                warp::reply::json(&PersonView::new(&lineage, added)),
+                StatusCode::CREATED,
+            )
+            .into_response()
+        }
+    })
+}
+
+/// GET /people/{name} => 200 OK with the person record
+pub fn get_person_route(
+    lineage_ref: Arc<RwLock<Lineage>>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("people" / String)
+        .map(decode_name)
+        .and(warp::get())
+        .and(warp::any().map(move || lineage_ref.clone()))
+        .and_then(get_person)
+}
+
+async fn get_person(
+    name: String,
+    lineage: Arc<RwLock<Lineage>>,
+) -> Result<impl warp::Reply, Infallible> {
+    let lineage = lineage.read().await;
+    Ok(match lineage.resolve(&name) {
+        Err(e) => ApiError::unresolved(&lineage, e).into_response(),
+        Ok(person) => warp::reply::json(&PersonView::new(&lineage, person)).into_response(),
+    })
+}
+
+/// GET /people/{name}/relatives => 200 OK with the close relatives of the person
+pub fn get_relatives_route(
+    lineage_ref: Arc<RwLock<Lineage>>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("people" / String / "relatives")
+        .map(decode_name)
+        .and(warp::get())
+        .and(warp::any().map(move || lineage_ref.clone()))
+        .and_then(get_relatives)
+}
+
+async fn get_relatives(
+    name: String,
+    lineage: Arc<RwLock<Lineage>>,
+) -> Result<impl warp::Reply, Infallible> {
+    let lineage = lineage.read().await;
+    Ok(match lineage.resolve(&name) {
+        Err(e) => ApiError::unresolved(&lineage, e).into_response(),
+        Ok(person) => {
+            let summaries = |mut people: Vec<&Person>| -> Vec<PersonSummary> {
+                people.sort();
+                people.into_iter().map(PersonSummary::new).collect()
+            };
+            warp::reply::json(&RelativesResponse {
+                person: PersonSummary::new(person),
+                father: lineage.get_father_of(person).map(PersonSummary::new),
+                mother: lineage.get_mother_of(person).map(PersonSummary::new),
+                sons: summaries(lineage.get_sons_of(person)),
+                daughters: summaries(lineage.get_daughters_of(person)),
+                brothers: summaries(lineage.get_brothers(person)),
+                sisters: summaries(lineage.get_sisters(person)),
+                nephews: summaries(lineage.get_nephews(person)),
+                nieces: summaries(lineage.get_nieces(person)),
+                grandparents: summaries(lineage.get_grandparents(person)),
+                grandchildren: summaries(lineage.get_grandchildren(person)),
+                uncles: summaries(lineage.get_uncles(person)),
+                aunts: summaries(lineage.get_aunts(person)),
+                cousins: summaries(lineage.get_cousins(person, 1)),
+                siblings: {
+                    let mut siblings = lineage.get_siblings_with_kind(person);
+                    siblings.sort_by_key(|(sibling, _)| *sibling);
+                    siblings
+                        .into_iter()
+                        .map(|(sibling, kind)| SiblingSummary {
+                            kind,
+                            person: PersonSummary::new(sibling),
+                        })
+                        .collect()
+                },
+            })
+            .into_response()
+        }
+    })
+}
+
+/// GET /people/{name}/ancestors?max_depth={n} => 200 OK with the ancestors of the person,
+/// generation by generation
+pub fn get_ancestors_route(
+    lineage_ref: Arc<RwLock<Lineage>>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("people" / String / "ancestors")
+        .map(decode_name)
+        .and(warp::get())
+        .and(warp::query::<AncestorsQuery>())
+        .and(warp::any().map(move || lineage_ref.clone()))
+        .and_then(get_ancestors)
+}
+
+async fn get_ancestors(
+    name: String,
+    query: AncestorsQuery,
+    lineage: Arc<RwLock<Lineage>>,
+) -> Result<impl warp::Reply, Infallible> {
+    let lineage = lineage.read().await;
+    Ok(match lineage.resolve(&name) {
+        Err(e) => ApiError::unresolved(&lineage, e).into_response(),
+        Ok(person) => {
+            let ancestors: Vec<GenerationMember> = lineage
+                .ancestors(person, query.max_depth)
+                .map(|(ancestor, generation)| GenerationMember::new(ancestor, generation))
+                .collect();
+            warp::reply::json(&ancestors).into_response()
+        }
+    })
+}
+
+/// GET /people/{name}/descendants?max_depth={n}&house={house}&alive={bool}&sex={M|F} => 200 OK
+/// with the matching descendants of the person, generation by generation
+pub fn get_descendants_route(
+    lineage_ref: Arc<RwLock<Lineage>>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("people" / String / "descendants")
+        .map(decode_name)
+        .and(warp::get())
+        .and(warp::query::<DescendantsQuery>())
+        .and(warp::any().map(move || lineage_ref.clone()))
+        .and_then(get_descendants)
+}
+
+async fn get_descendants(
+    name: String,
+    query: DescendantsQuery,
+    lineage: Arc<RwLock<Lineage>>,
+) -> Result<impl warp::Reply, Infallible> {
+    let lineage = lineage.read().await;
+    Ok(match lineage.resolve(&name) {
+        Err(e) => ApiError::unresolved(&lineage, e).into_response(),
+        Ok(person) => {
+            let filter = PersonFilter {
+                alive: query.alive,
+                sex: query.sex,
+                house: query.house,
+            };
+            let descendants: Vec<GenerationMember> = lineage
+                .descendants(person, query.max_depth, filter)
+                .map(|(descendant, generation)| GenerationMember::new(descendant, generation))
+                .collect();
+            warp::reply::json(&descendants).into_response()
+        }
+    })
+}
+
+pub fn endpoints() -> Vec<Endpoint> {
+    let name = |endpoint: Endpoint| {
+        endpoint.path_param("name", "Id or name of the person, url encoded", "Son House")
+    };
+    vec![
+        Endpoint::new(
+            "get",
+            "/people",
+            "The people matching all the filters, ordered alphabetically",
+        )
+        .query::<String>("house", false, "House name", "House")
+        .query::<bool>("alive", false, "Only the living, or the dead", "true")
+        .query::<Sex>("sex", false, "M or F", "M")
+        .response(Response::json::<Vec<PersonView>>(
+            200,
+            "The matching people",
+        ))
+        .response(Response::error(400, "INVALID_QUERY")),
+        Endpoint::new("post", "/people", "Adds a person without relatives")
+            .json_body::<NewPerson>()
+            .response(Response::json::<PersonView>(201, "The added person"))
+            .response(Response::error(400, "INVALID_BODY"))
+            .response(Response::error(409, "PERSON_ALREADY_EXISTS"))
+            .response(Response::error(422, "INVALID_NAME")),
+        name(Endpoint::new(
+            "get",
+            "/people/{name}",
+            "Record of the person",
+        ))
+        .response(Response::json::<PersonView>(200, "The person"))
+        .response(Response::error(404, "PERSON_NOT_FOUND"))
+        .response(Response::error(409, "AMBIGUOUS_NAME")),
+        name(Endpoint::new(
+            "get",
+            "/people/{name}/relatives",
+            "Close relatives of the person",
+        ))
+        .response(Response::json::<RelativesResponse>(
+            200,
+            "The relatives, ordered alphabetically",
+        ))
+        .response(Response::error(404, "PERSON_NOT_FOUND"))
+        .response(Response::error(409, "AMBIGUOUS_NAME")),
+        name(Endpoint::new(
+            "get",
+            "/people/{name}/ancestors",
+            "Parents, grandparents and so on, generation by generation",
+        ))
+        .query::<usize>(
+            "max_depth",
+            false,
+            "Generations to go up, all of them if missing",
+            "2",
+        )
+        .response(Response::json::<Vec<GenerationMember>>(
+            200,
+            "The ancestors, fathers before mothers",
+        ))
+        .response(Response::error(400, "INVALID_QUERY"))
+        .response(Response::error(404, "PERSON_NOT_FOUND"))
+        .response(Response::error(409, "AMBIGUOUS_NAME")),
+        name(Endpoint::new(
+            "get",
+            "/people/{name}/descendants",
+            "Children, grandchildren and so on matching all the filters, generation by generation",
+        ))
+        .query::<usize>(
+            "max_depth",
+            false,
+            "Generations to go down, all of them if missing",
+            "2",
+        )
+        .query::<String>("house", false, "House name", "House")
+        .query::<bool>("alive", false, "Only the living, or the dead", "true")
+        .query::<Sex>("sex", false, "M or F", "M")
+        .response(Response::json::<Vec<GenerationMember>>(
+            200,
+            "The descendants, sons before daughters",
+        ))
+        .response(Response::error(400, "INVALID_QUERY"))
+        .response(Response::error(404, "PERSON_NOT_FOUND"))
+        .response(Response::error(409, "AMBIGUOUS_NAME")),
+    ]
+}
@@ -0,0 +1,181 @@
+use super::changes::ChangeLog;
+use super::error::ApiError;
+use super::json_body;
+use super::model::{PersonView, RelationshipResponse};
+use super::openapi::{Endpoint, Response};
+use crate::lineage::{InsertError, Lineage, ParentChildInfo, UnlinkError};
+use crate::persistence::Event;
+use async_std::sync::{Arc, RwLock};
+use serde::Deserialize;
+use std::convert::Infallible;
+use warp::http::StatusCode;
+use warp::{Filter, Reply};
+
+/// Query of DELETE /relationships, ids or names
+#[derive(Debug, Deserialize)]
+pub struct RelationshipQuery {
+    parent: String,
+    child: String,
+}
+
+fn relationship_response(lineage: &Lineage, parent_id: &str, child_id: &str) -> impl Reply {
+    let view = |id: &str| {
+        let person = lineage
+            .get_from_id(id)
+            .expect("people of a relationship must exist");
+        PersonView::new(lineage, person)
+    };
+    warp::reply::json(&RelationshipResponse {
+        parent: view(parent_id),
+        child: view(child_id),
+    })
+}
+
+/// The relationship with the ids of the people it links once inserted, without an id the name
+/// is borne by a single person
+fn with_ids(lineage: &Lineage, parent_child_info: &ParentChildInfo) -> ParentChildInfo {
+    let id = |id: &Option<String>, name: &str| match id {
+        Some(id) => id.clone(),
+        None => lineage
+            .get_from_name(name)
+            .expect("people of a relationship must exist")
+            .id()
+            .to_string(),
+    };
+    ParentChildInfo {
+        parent_id: Some(id(
+            &parent_child_info.parent_id,
+            &parent_child_info.parent_name,
+        )),
+        child_id: Some(id(
+            &parent_child_info.child_id,
+            &parent_child_info.child_name,
+        )),
+        ..parent_child_info.clone()
+    }
+}
+
+/// POST /relationships with a `ParentChildInfo` body => 201 CREATED with the parent and child
+/// records. Unknown people are created, as when loading a dataset.
+pub fn add_relationship_route(
+    lineage_ref: Arc<RwLock<Lineage>>,
+    changes: Arc<ChangeLog>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("relationships")
+        .and(warp::post())
+        .and(json_body::<ParentChildInfo>())
+        .and(warp::any().map(move || lineage_ref.clone()))
+        .and(warp::any().map(move || changes.clone()))
+        .and_then(add_relationship)
+}
+
+async fn add_relationship(
+    parent_child_info: ParentChildInfo,
+    lineage: Arc<RwLock<Lineage>>,
+    changes: Arc<ChangeLog>,
+) -> Result<impl warp::Reply, Infallible> {
+    let mut lineage = lineage.write().await;
+    let inserted: Result<_, InsertError> = changes.apply(&mut lineage, |lineage| {
+        let changes = lineage.try_insert(parent_child_info.clone())?;
+        // the ids of the new people are journaled so they are the same when replaying
+        let inserted = with_ids(lineage, &parent_child_info);
+        let event = Event::AddRelationship(inserted.clone());
+        Ok((inserted, changes, vec![event]))
+    });
+    Ok(match inserted {
+        Err(e) => ApiError::from(e).into_response(),
+        Ok((inserted, _)) => {
+            let (parent_id, child_id) = (
+                inserted.parent_id.expect("ids are set"),
+                inserted.child_id.expect("ids are set"),
+            );
+            warp::reply::with_status(
+                relationship_response(&lineage, &parent_id, &child_id),
+                StatusCode::CREATED,
+            )
+            .into_response()
+        }
+    })
+}
+
+/// DELETE /relationships?parent={name}&child={name} => 200 OK with the parent and child records
+pub fn remove_relationship_route(
+    lineage_ref: Arc<RwLock<Lineage>>,
+    changes: Arc<ChangeLog>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("relationships")
+        .and(warp::delete())
+        .and(warp::query::<RelationshipQuery>())
+        .and(warp::any().map(move || lineage_ref.clone()))
+        .and(warp::any().map(move || changes.clone()))
+        .and_then(remove_relationship)
+}
+
+async fn remove_relationship(
+    query: RelationshipQuery,
+    lineage: Arc<RwLock<Lineage>>,
+    changes: Arc<ChangeLog>,
+) -> Result<impl warp::Reply, Infallible> {
+    let mut lineage = lineage.write().await;
+    let mut ids = vec![];
+    for key in &[&query.parent, &query.child] {
+        match lineage.resolve(key) {
+            Ok(person) => ids.push(person.id().to_string()),
+            Err(e) => return Ok(ApiError::unresolved(&lineage, e).into_response()),
+        }
+    }
+    let (parent_id, child_id) = (&ids[0], &ids[1]);
+    let removed: Result<_, UnlinkError> = changes.apply(&mut lineage, |lineage| {
+        let changes = lineage.remove_relationship(parent_id, child_id)?;
+        Ok((
+            (),
+            changes,
+            vec![Event::RemoveRelationship {
+                parent_id: parent_id.clone(),
+                child_id: child_id.clone(),
+            }],
+        ))
+    });
+    Ok(match removed {
+        Err(e) => ApiError::from(e).into_response(),
+        Ok(_) => relationship_response(&lineage, parent_id, child_id).into_response(),
+    })
+}
+
+pub fn endpoints() -> Vec<Endpoint> {
+    vec![
+        Endpoint::new(
+            "post",
+            "/relationships",
+            "Adds a parent child relationship, creating unknown people",
+        )
+        .json_body::<ParentChildInfo>()
+        .response(Response::json::<RelationshipResponse>(
+            201,
+            "The parent and the child",
+        ))
+        .response(Response::error(400, "INVALID_BODY"))
+        .response(Response::error(
+            409,
+            "SEX_MISMATCH, RELATIONSHIP_ALREADY_EXISTS, PARENT_ALREADY_SET, AMBIGUOUS_NAME or \
+             ID_CONFLICT",
+        ))
+        .response(Response::error(422, "INVALID_NAME or CYCLE")),
+        Endpoint::new(
+            "delete",
+            "/relationships",
+            "Removes a parent child relationship",
+        )
+        .query::<String>("parent", true, "Id or name of the parent", "Father House")
+        .query::<String>("child", true, "Id or name of the child", "Son House")
+        .response(Response::json::<RelationshipResponse>(
+            200,
+            "The parent and the child",
+        ))
+        .response(Response::error(
+            404,
+            "PERSON_NOT_FOUND or RELATIONSHIP_NOT_FOUND",
+        ))
+        .response(Response::error(409, "AMBIGUOUS_NAME")),
+    ]
+}
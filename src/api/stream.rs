@@ -0,0 +1,51 @@
+use super::changes::ChangeLog;
+use super::openapi::{schema, Endpoint, Response};
+use crate::lineage::SuccessionChange;
+use async_std::sync::Arc;
+use futures::{future, StreamExt};
+use std::convert::Infallible;
+use tokio::sync::broadcast::RecvError;
+use warp::sse::ServerSentEvent;
+use warp::Filter;
+
+/// GET /successions/stream => Server-Sent Events stream with a `succession_change` event, whose
+/// data is a JSON `SuccessionChange`, every time a change to the lineage changes someone's heir
+pub fn successions_stream_route(
+    changes: Arc<ChangeLog>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("successions" / "stream")
+        .and(warp::get())
+        .map(move || {
+            let events = changes.subscribe().filter_map(|received| {
+                future::ready(match received {
+                    Ok(change) => Some(Ok::<_, Infallible>(to_event(change))),
+                    Err(RecvError::Lagged(missed)) => {
+                        log::warn!("Succession stream subscriber missed {} changes", missed);
+                        None
+                    }
+                    Err(RecvError::Closed) => None,
+                })
+            });
+            warp::sse::reply(warp::sse::keep_alive().stream(events))
+        })
+}
+
+fn to_event(change: crate::lineage::SuccessionChange) -> impl ServerSentEvent {
+    (
+        warp::sse::event("succession_change"),
+        warp::sse::json(change),
+    )
+}
+
+pub fn endpoints() -> Vec<Endpoint> {
+    vec![Endpoint::new(
+        "get",
+        "/successions/stream",
+        "Server-Sent Events stream of the heir changes",
+    )
+    .response(Response {
+        status: 200,
+        description: "succession_change events, each with a SuccessionChange as data",
+        content: vec![("text/event-stream", schema::<SuccessionChange>)],
+    })]
+}
@@ -0,0 +1,68 @@
+use super::create_routes;
+use serde_json::{json, Value};
+use warp::http::StatusCode;
+
+#[tokio::test]
+async fn batch_kill_applies_all_or_nothing() {
+    let routes = create_routes();
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/kill/batch")
+        .json(&json!({"names": ["Son House", "Nobody House"]}))
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(
+        body,
+        json!({
+            "applied": false,
+            "results": [
+                {"name": "Son House", "outcome": "SKIPPED"},
+                {"name": "Nobody House", "outcome": "PERSON_NOT_FOUND"},
+            ],
+            "succession_changes": []
+        })
+    );
+    let resp = warp::test::request()
+        .path("/successor?name=Father%20House")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.body(), "Son House");
+
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/kill/batch")
+        .json(&json!({"names": ["Son House", "Daughter House", "Son House"]}))
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(body["applied"], true);
+    assert_eq!(body["results"][0]["outcome"], "KILLED");
+    assert_eq!(body["results"][1]["outcome"], "KILLED");
+    assert_eq!(body["results"][2]["outcome"], "ALREADY_DEAD");
+    assert_eq!(
+        body["succession_changes"],
+        json!([
+            {"person": "Father House", "old_successor": "Son House", "new_successor": "Mother House"},
+            {"person": "Mother House", "old_successor": "Son House", "new_successor": "Father House"},
+        ])
+    );
+    let resp = warp::test::request()
+        .path("/successor?name=Father%20House")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.body(), "Mother House");
+}
+
+#[tokio::test]
+async fn batch_kill_requires_names() {
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/kill/batch")
+        .json(&json!(["Son House"]))
+        .reply(&create_routes())
+        .await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,99 @@
+use super::create_routes;
+use serde_json::{json, Value};
+use warp::http::StatusCode;
+
+async fn request(method: &str, path: &str) -> (StatusCode, Value) {
+    let resp = warp::test::request()
+        .method(method)
+        .path(path)
+        .reply(&create_routes())
+        .await;
+    assert_eq!(resp.headers()["content-type"], "application/json");
+    (resp.status(), serde_json::from_slice(resp.body()).unwrap())
+}
+
+#[tokio::test]
+async fn person_not_found_in_every_version() {
+    for (method, path) in &[
+        ("GET", "/successor?name=Nobody%20House"),
+        ("POST", "/kill?name=Nobody%20House"),
+        ("GET", "/v2/successor?name=Nobody%20House"),
+        ("POST", "/v2/kill?name=Nobody%20House"),
+    ] {
+        let (status, body) = request(method, path).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "PERSON_NOT_FOUND");
+        assert_eq!(body["message"], "Nobody House is not part of the lineage");
+        assert!(body["suggestion"].is_string());
+    }
+}
+
+#[tokio::test]
+async fn person_not_found_suggests_close_names() {
+    for (method, path) in &[
+        ("GET", "/successor?name=Fathr%20Huse"),
+        ("POST", "/kill?name=Fathr%20Huse"),
+        ("GET", "/v2/successor?name=Fathr%20Huse"),
+        ("POST", "/v2/kill?name=Fathr%20Huse"),
+    ] {
+        let (status, body) = request(method, path).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "PERSON_NOT_FOUND");
+        assert_eq!(body["did_you_mean"], json!(["Father House"]));
+        assert_eq!(body["suggestion"], "Did you mean Father House?");
+    }
+    let (_, body) = request("GET", "/successor?name=Nobody%20House").await;
+    assert!(body.get("did_you_mean").is_none());
+}
+
+#[tokio::test]
+async fn already_dead_is_a_conflict_in_v2() {
+    let routes = create_routes();
+    for expected_status in &[StatusCode::OK, StatusCode::CONFLICT] {
+        let resp = warp::test::request()
+            .method("POST")
+            .path("/v2/kill?name=Son%20House")
+            .reply(&routes)
+            .await;
+        assert_eq!(resp.status(), *expected_status);
+    }
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/v2/kill?name=Son%20House")
+        .reply(&routes)
+        .await;
+    let body: Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(
+        body,
+        json!({
+            "code": "ALREADY_DEAD",
+            "message": "Son House was already dead",
+            "suggestion": "GET /v2/successor?name=Son%20House tells who inherited",
+        })
+    );
+}
+
+#[tokio::test]
+async fn missing_name_is_an_invalid_query() {
+    for (method, path) in &[
+        ("GET", "/successor"),
+        ("GET", "/v2/successor?nom=Son%20House"),
+        ("POST", "/kill"),
+    ] {
+        let (status, body) = request(method, path).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "INVALID_QUERY");
+    }
+}
+
+#[tokio::test]
+async fn unmatched_routes_are_json() {
+    let (status, body) = request("GET", "/heir?name=Son%20House").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "ROUTE_NOT_FOUND");
+    assert_eq!(body["suggestion"], Value::Null);
+
+    let (status, body) = request("DELETE", "/successor?name=Son%20House").await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(body["code"], "METHOD_NOT_ALLOWED");
+}
@@ -0,0 +1,26 @@
+use super::create_routes;
+use warp::http::StatusCode;
+
+#[tokio::test]
+async fn explorer_is_served_at_the_root() {
+    let resp = warp::test::request()
+        .path("/")
+        .reply(&create_routes())
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "text/html; charset=utf-8");
+    let page = String::from_utf8(resp.body().to_vec()).unwrap();
+    assert!(page.starts_with("<!DOCTYPE html>"));
+    // the page only uses routes of the API, nothing from elsewhere
+    for route in &[
+        "\"/people\"",
+        "\"/people/\"",
+        "\"/v2/successor?name=\"",
+        "\"/graph.svg?name=\"",
+        "\"/v2/kill?name=\"",
+        "\"/graphql\"",
+    ] {
+        assert!(page.contains(route), "{} is not used", route);
+    }
+    assert!(!page.contains("http://") && !page.contains("https://"));
+}
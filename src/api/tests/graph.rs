@@ -0,0 +1,160 @@
+use super::create_routes;
+use serde_json::{json, Value};
+use warp::http::StatusCode;
+
+async fn get(path: &str) -> warp::http::Response<warp::hyper::body::Bytes> {
+    warp::test::request()
+        .path(path)
+        .reply(&create_routes())
+        .await
+}
+
+fn ids(values: &Value) -> Vec<&str> {
+    values
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|value| value.as_str().or_else(|| value["id"].as_str()).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn graph_defaults_to_dot() {
+    let resp = get("/graph?name=Daughter%20House").await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "text/vnd.graphviz");
+    let dot = String::from_utf8(resp.body().to_vec()).unwrap();
+    assert!(dot.starts_with("digraph lineage {"));
+    assert!(dot.contains(r#"subgraph "cluster_House""#));
+    assert!(dot.contains(r#""father-house+mother-house" [shape=point];"#));
+    // the heir is the son, linked to the person through the couple node
+    assert!(dot.contains(
+        r#""daughter-house" -> "husband-other+daughter-house" [arrowhead=none, color=gold3"#
+    ));
+}
+
+#[tokio::test]
+async fn graph_is_limited_to_the_neighbourhood_and_the_heir_path() {
+    let resp = get("/graph?name=Son%20House&up=0&down=0&format=json").await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(body["person"]["id"], "son-house");
+    assert_eq!(body["heir"]["id"], "grandson-other");
+    assert_eq!(
+        ids(&body["heir_path"]),
+        vec![
+            "son-house",
+            "father-house",
+            "daughter-house",
+            "grandson-other"
+        ]
+    );
+    // the mother is not on the path
+    assert_eq!(
+        ids(&body["people"]),
+        vec![
+            "son-house",
+            "father-house",
+            "daughter-house",
+            "grandson-other"
+        ]
+    );
+    assert_eq!(body["links"].as_array().unwrap().len(), 3);
+    assert_eq!(body["links"][0]["parent"], "father-house");
+    assert_eq!(body["links"][0]["child"], "son-house");
+
+    let resp = get("/graph?name=Son%20House&up=1&down=0&siblings=true&format=json").await;
+    let body: Value = serde_json::from_slice(resp.body()).unwrap();
+    assert!(ids(&body["people"]).contains(&"mother-house"));
+}
+
+#[tokio::test]
+async fn graph_in_svg_is_drawn_by_the_crate() {
+    let resp = get("/graph?name=Son%20House&format=svg").await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "image/svg+xml");
+    let svg = String::from_utf8(resp.body().to_vec()).unwrap();
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.contains(r#"id="grandson-other""#));
+}
+
+#[tokio::test]
+async fn graph_svg_draws_a_person_or_the_whole_lineage() {
+    let resp = get("/graph.svg?name=Grandson%20Other&up=1&down=0").await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "image/svg+xml");
+    let svg = String::from_utf8(resp.body().to_vec()).unwrap();
+    assert!(svg.contains(r#"id="husband-other""#));
+    assert!(!svg.contains(r#"id="mother-house""#));
+
+    let resp = get("/graph.svg").await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let svg = String::from_utf8(resp.body().to_vec()).unwrap();
+    for id in &[
+        "mother-house",
+        "father-house",
+        "son-house",
+        "daughter-house",
+        "husband-other",
+        "grandson-other",
+    ] {
+        assert!(
+            svg.contains(&format!(r#"id="{}""#, id)),
+            "{} is missing",
+            id
+        );
+    }
+
+    let resp = get("/graph.svg?name=Nobody%20House").await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn graph_rejects_unknown_people_and_formats() {
+    let resp = get("/graph?name=Nobody%20House").await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let resp = get("/graph?name=Son%20House&format=png").await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn graph_as_text_is_the_tree_of_the_descendants() {
+    let resp = get("/graph?name=Mother%20House&down=1&format=text").await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "text/plain; charset=utf-8");
+    assert_eq!(
+        String::from_utf8(resp.body().to_vec()).unwrap(),
+        "Mother House\n├── Son House ← heir\n└── Daughter House\n"
+    );
+}
+
+#[tokio::test]
+async fn heir_of_the_house_is_drawn_without_a_path() {
+    let routes = create_routes();
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/people")
+        .json(&json!({"name": "Lonely House", "sex": "M"}))
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    // no blood relative, the heir comes from the house tier
+    let resp = warp::test::request()
+        .path("/graph?name=Lonely%20House&format=json")
+        .reply(&routes)
+        .await;
+    let body: Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(body["heir"]["id"], "daughter-house");
+    assert_eq!(body["heir_path"], json!([]));
+    assert_eq!(ids(&body["people"]), vec!["daughter-house", "lonely-house"]);
+
+    let resp = warp::test::request()
+        .path("/graph?name=Lonely%20House")
+        .reply(&routes)
+        .await;
+    let dot = String::from_utf8(resp.body().to_vec()).unwrap();
+    assert!(dot.contains(
+        r#""daughter-house" [label="Daughter House", shape=ellipse, penwidth=3, color=gold3];"#
+    ));
+}
@@ -0,0 +1,83 @@
+use super::create_routes;
+use serde_json::{json, Value};
+use warp::http::StatusCode;
+
+async fn graphql<F>(routes: &F, query: &str) -> (StatusCode, Value)
+where
+    F: warp::Filter + 'static,
+    F::Extract: warp::Reply + Send,
+{
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/graphql")
+        .json(&json!({ "query": query }))
+        .reply(routes)
+        .await;
+    (resp.status(), serde_json::from_slice(resp.body()).unwrap())
+}
+
+#[tokio::test]
+async fn fetches_nested_relatives() {
+    let routes = create_routes();
+    let (status, body) = graphql(
+        &routes,
+        r#"{
+            person(name: "Mother House") {
+                sex
+                sons { name successor { name } }
+                daughters { name sons { father { house } } }
+                successor { name }
+            }
+        }"#,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body,
+        json!({"data": {"person": {
+            "sex": "FEMALE",
+            "sons": [{"name": "Son House", "successor": {"name": "Grandson Other"}}],
+            "daughters": [{"name": "Daughter House", "sons": [{"father": {"house": "Other"}}]}],
+            "successor": {"name": "Son House"}
+        }}})
+    );
+
+    let (_, body) = graphql(&routes, r#"{ people(house: "Other") { name } }"#).await;
+    assert_eq!(
+        body["data"]["people"],
+        json!([{"name": "Grandson Other"}, {"name": "Husband Other"}])
+    );
+    let (_, body) = graphql(&routes, r#"{ person(name: "Nobody House") { name } }"#).await;
+    assert_eq!(body["data"]["person"], Value::Null);
+}
+
+#[tokio::test]
+async fn kill_and_revive_mutations() {
+    let routes = create_routes();
+    let kill = r#"mutation { kill(name: "Son House") {
+        person { alive }
+        successionChanges { person oldSuccessor newSuccessor }
+    } }"#;
+    let (status, body) = graphql(&routes, kill).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["kill"]["person"]["alive"], false);
+    assert_eq!(
+        body["data"]["kill"]["successionChanges"][0],
+        json!({"person": "Father House", "oldSuccessor": "Son House", "newSuccessor": "Daughter House"})
+    );
+
+    let (_, body) = graphql(&routes, kill).await;
+    assert_eq!(body["errors"][0]["extensions"]["code"], "ALREADY_DEAD");
+
+    let revive = r#"mutation Revive { revive(name: "Son House") { person { name alive } } }"#;
+    let (_, body) = graphql(&routes, revive).await;
+    assert_eq!(
+        body["data"]["revive"]["person"],
+        json!({"name": "Son House", "alive": true})
+    );
+    let (_, body) = graphql(&routes, revive).await;
+    assert_eq!(body["errors"][0]["extensions"]["code"], "ALREADY_ALIVE");
+
+    let (status, _) = graphql(&routes, "{ person(name: ").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
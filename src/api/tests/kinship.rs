@@ -0,0 +1,53 @@
+use super::create_routes;
+use serde_json::{json, Value};
+use warp::http::StatusCode;
+
+async fn get(path: &str) -> (StatusCode, Value) {
+    let resp = warp::test::request()
+        .path(path)
+        .reply(&create_routes())
+        .await;
+    (resp.status(), serde_json::from_slice(resp.body()).unwrap())
+}
+
+#[tokio::test]
+async fn relationship_has_name_and_path() {
+    let (status, body) = get("/relationship?a=Son%20House&b=grandson-other").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["a"]["name"], "Son House");
+    assert_eq!(body["b"]["name"], "Grandson Other");
+    let relationship = &body["relationship"];
+    assert_eq!(relationship["name"], "nephew");
+    assert_eq!(relationship["generations_up"], 1);
+    assert_eq!(relationship["generations_down"], 2);
+    assert_eq!(relationship["half"], false);
+    assert_eq!(
+        relationship["common_ancestors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|ancestor| ancestor["name"].as_str().unwrap())
+            .collect::<Vec<_>>(),
+        vec!["Father House", "Mother House"]
+    );
+    assert_eq!(
+        relationship["path"],
+        json!([
+            {"name": "Son House", "relation": null},
+            {"name": "Father House", "relation": "father"},
+            {"name": "Daughter House", "relation": "daughter"},
+            {"name": "Grandson Other", "relation": "son"},
+        ])
+    );
+}
+
+#[tokio::test]
+async fn relationship_is_null_without_common_ancestor() {
+    let (status, body) = get("/relationship?a=Son%20House&b=Husband%20Other").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["relationship"], Value::Null);
+
+    let (status, body) = get("/relationship?a=Son%20House&b=Nobody%20House").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "PERSON_NOT_FOUND");
+}
@@ -0,0 +1,80 @@
+mod batch;
+mod errors;
+mod explorer;
+mod graph;
+mod graphql;
+mod kinship;
+mod mutations;
+mod openapi;
+mod people;
+mod stream;
+mod v1;
+mod v2;
+
+use super::ChangeLog;
+use crate::lineage::{
+    Lineage, ParentChildInfo,
+    Sex::{Female, Male},
+};
+use crate::persistence::Journal;
+use async_std::sync::{Arc, RwLock};
+use std::convert::Infallible;
+use warp::Filter;
+
+/// Creates the following lineage, behind the routes of the application
+///
+///            (Mother House + Father House)
+///              /             \
+///       Son House        (Daughter House + Husband Other)
+///                                  |
+///                             Grandson Other
+fn create_routes() -> impl Filter<Extract = impl warp::Reply, Error = Infallible> + Clone {
+    let (lineage, changes) = create_lineage_and_changes();
+    super::routes(lineage, changes)
+}
+
+/// The lineage behind `create_routes` and its change log
+fn create_lineage_and_changes() -> (Arc<RwLock<Lineage>>, Arc<ChangeLog>) {
+    let mut lineage = Lineage::new();
+    lineage.insert(ParentChildInfo::new(
+        "Mother House",
+        Female,
+        "Son House",
+        Male,
+    ));
+    lineage.insert(ParentChildInfo::new(
+        "Father House",
+        Male,
+        "Son House",
+        Male,
+    ));
+    lineage.insert(ParentChildInfo::new(
+        "Mother House",
+        Female,
+        "Daughter House",
+        Female,
+    ));
+    lineage.insert(ParentChildInfo::new(
+        "Father House",
+        Male,
+        "Daughter House",
+        Female,
+    ));
+    lineage.insert(ParentChildInfo::new(
+        "Daughter House",
+        Female,
+        "Grandson Other",
+        Male,
+    ));
+    lineage.insert(ParentChildInfo::new(
+        "Husband Other",
+        Male,
+        "Grandson Other",
+        Male,
+    ));
+    lineage.add_alias("Sonny", "Son House").unwrap();
+    (
+        Arc::new(RwLock::new(lineage)),
+        Arc::new(ChangeLog::new(Journal::disabled())),
+    )
+}
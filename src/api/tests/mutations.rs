@@ -0,0 +1,182 @@
+use super::create_routes;
+use serde_json::{json, Value};
+use warp::http::StatusCode;
+
+fn body_json(resp: &warp::http::Response<warp::hyper::body::Bytes>) -> Value {
+    serde_json::from_slice(resp.body()).unwrap()
+}
+
+#[tokio::test]
+async fn can_add_person_and_relationship() {
+    let routes = create_routes();
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/people")
+        .json(&json!({"name": "Bastard House", "sex": "M"}))
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    assert_eq!(body_json(&resp)["name"], "Bastard House");
+
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/relationships")
+        .json(&json!({
+            "parent_name": "Son House",
+            "parent_sex": "M",
+            "child_name": "Bastard House",
+            "child_sex": "M",
+        }))
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let body = body_json(&resp);
+    assert_eq!(body["parent"]["sons"], json!(["Bastard House"]));
+    assert_eq!(body["child"]["father"], "Son House");
+
+    let resp = warp::test::request()
+        .path("/successor?name=Son%20House")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.body(), "Bastard House");
+}
+
+#[tokio::test]
+async fn rejects_invalid_mutations() {
+    let routes = create_routes();
+    let cases = vec![
+        (
+            "/people",
+            json!({"name": "Son House", "sex": "M"}),
+            StatusCode::CONFLICT,
+            "PERSON_ALREADY_EXISTS",
+        ),
+        (
+            "/people",
+            json!({"name": "Nameless", "sex": "F"}),
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "INVALID_NAME",
+        ),
+        (
+            "/people",
+            json!({"name": "Someone House"}),
+            StatusCode::BAD_REQUEST,
+            "INVALID_BODY",
+        ),
+        (
+            "/relationships",
+            json!({"parent_name": "Son House", "parent_sex": "F",
+                   "child_name": "New House", "child_sex": "M"}),
+            StatusCode::CONFLICT,
+            "SEX_MISMATCH",
+        ),
+        (
+            "/relationships",
+            json!({"parent_name": "Grandson Other", "parent_sex": "M",
+                   "child_name": "Husband Other", "child_sex": "M"}),
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "CYCLE",
+        ),
+        (
+            "/relationships",
+            json!({"parent_name": "Father House", "parent_sex": "M",
+                   "child_name": "Son House", "child_sex": "M"}),
+            StatusCode::CONFLICT,
+            "RELATIONSHIP_ALREADY_EXISTS",
+        ),
+        (
+            "/relationships",
+            json!({"parent_name": "Other Father", "parent_sex": "M",
+                   "child_name": "Son House", "child_sex": "M"}),
+            StatusCode::CONFLICT,
+            "PARENT_ALREADY_SET",
+        ),
+    ];
+    for (path, body, status, code) in cases {
+        let resp = warp::test::request()
+            .method("POST")
+            .path(path)
+            .json(&body)
+            .reply(&routes)
+            .await;
+        assert_eq!(resp.status(), status, "{}", body);
+        assert_eq!(body_json(&resp)["code"], code);
+    }
+    // nothing was added
+    let resp = warp::test::request().path("/people").reply(&routes).await;
+    assert_eq!(body_json(&resp).as_array().unwrap().len(), 6);
+}
+
+#[tokio::test]
+async fn can_remove_relationship() {
+    let routes = create_routes();
+    let resp = warp::test::request()
+        .method("DELETE")
+        .path("/relationships?parent=Daughter%20House&child=Grandson%20Other")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = body_json(&resp);
+    assert_eq!(body["parent"]["sons"], json!([]));
+    assert_eq!(body["child"]["mother"], Value::Null);
+
+    let resp = warp::test::request()
+        .method("DELETE")
+        .path("/relationships?parent=Daughter%20House&child=Grandson%20Other")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert_eq!(body_json(&resp)["code"], "RELATIONSHIP_NOT_FOUND");
+
+    let resp = warp::test::request()
+        .method("DELETE")
+        .path("/relationships?parent=Nobody%20House&child=Grandson%20Other")
+        .reply(&routes)
+        .await;
+    assert_eq!(body_json(&resp)["code"], "PERSON_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn homonyms_need_ids() {
+    let routes = create_routes();
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/people")
+        .json(&json!({"name": "Son House", "sex": "M", "id": "second-son"}))
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    assert_eq!(body_json(&resp)["id"], "second-son");
+
+    for request in [
+        warp::test::request().path("/v2/successor?name=Son%20House"),
+        warp::test::request()
+            .method("POST")
+            .path("/kill?name=son%20house"),
+    ] {
+        let resp = request.reply(&routes).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+        let body = body_json(&resp);
+        assert_eq!(body["code"], "AMBIGUOUS_NAME");
+        let candidates: Vec<&str> = body["candidates"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|candidate| candidate["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(candidates, vec!["son-house", "second-son"]);
+    }
+
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/v2/kill?name=second-son")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(body_json(&resp)["killed"]["id"], "second-son");
+    let resp = warp::test::request()
+        .path("/people/son-house")
+        .reply(&routes)
+        .await;
+    assert_eq!(body_json(&resp)["alive"], true);
+}
@@ -0,0 +1,128 @@
+use super::{create_lineage_and_changes, create_routes};
+use crate::api::error::handle_rejection;
+use crate::api::openapi::{document, endpoints, Endpoint, Location};
+use crate::api::route_table;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::time::Duration;
+use warp::http::StatusCode;
+use warp::test::RequestBuilder;
+use warp::{Filter, Reply};
+
+/// Committed copy of the document, so changes to the API show up in reviews
+fn snapshot_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("openapi.json")
+}
+
+/// The request of the examples of the endpoint, and how it is described in assertions
+fn example_request(endpoint: &Endpoint) -> (String, RequestBuilder) {
+    let mut path = endpoint.path.to_string();
+    let mut query = vec![];
+    for parameter in &endpoint.parameters {
+        let example = utf8_percent_encode(parameter.example, NON_ALPHANUMERIC).to_string();
+        match parameter.location {
+            Location::Path => path = path.replace(&format!("{{{}}}", parameter.name), &example),
+            Location::Query => query.push(format!("{}={}", parameter.name, example)),
+        }
+    }
+    if !query.is_empty() {
+        path = format!("{}?{}", path, query.join("&"));
+    }
+    let mut request = warp::test::request()
+        .method(&endpoint.method.to_uppercase())
+        .path(&path);
+    if endpoint.request_body.is_some() {
+        // an invalid body is enough to know the route was matched
+        request = request.json(&serde_json::json!({}));
+    }
+    (format!("{} {}", endpoint.method, path), request)
+}
+
+/// Sends the example request of the endpoint, checking it matched a route with valid parameters
+async fn assert_example_is_answered<F>(endpoint: &Endpoint, routes: &F)
+where
+    F: Filter + 'static,
+    F::Extract: Reply + Send,
+{
+    let (described, request) = example_request(endpoint);
+    // streams never end, a timeout means the route was matched
+    let reply = tokio::time::timeout(Duration::from_millis(200), request.reply(routes));
+    let resp = match reply.await {
+        Ok(resp) => resp,
+        Err(_) => return,
+    };
+    assert_ne!(
+        resp.status(),
+        StatusCode::METHOD_NOT_ALLOWED,
+        "{}",
+        described
+    );
+    if let Ok(body) = serde_json::from_slice::<Value>(resp.body()) {
+        assert_ne!(body["code"], "ROUTE_NOT_FOUND", "{}", described);
+        // a renamed or missing parameter would still match the path
+        assert_ne!(body["code"], "INVALID_QUERY", "{}", described);
+    }
+}
+
+#[tokio::test]
+async fn every_documented_endpoint_is_routed() {
+    let routes = create_routes();
+    for endpoint in endpoints() {
+        assert_example_is_answered(&endpoint, &routes).await;
+    }
+}
+
+#[test]
+fn every_route_is_documented() {
+    let (lineage, changes) = create_lineage_and_changes();
+    let mut routed: Vec<(&str, &str)> = route_table(lineage, changes)
+        .into_iter()
+        .map(|(method, path, _)| (method, path))
+        .collect();
+    let mut documented: Vec<(&str, &str)> = endpoints()
+        .iter()
+        .map(|endpoint| (endpoint.method, endpoint.path))
+        .collect();
+    routed.sort_unstable();
+    documented.sort_unstable();
+    assert_eq!(routed, documented);
+}
+
+#[tokio::test]
+async fn each_route_answers_its_own_endpoint() {
+    let (lineage, changes) = create_lineage_and_changes();
+    let endpoints = endpoints();
+    for (method, path, route) in route_table(lineage, changes) {
+        let endpoint = endpoints
+            .iter()
+            .find(|endpoint| endpoint.method == method && endpoint.path == path)
+            .unwrap_or_else(|| panic!("{} {} is not documented", method, path));
+        assert_example_is_answered(endpoint, &route.recover(handle_rejection)).await;
+    }
+}
+
+#[test]
+fn document_matches_snapshot() {
+    let generated = serde_json::to_string_pretty(&document()).unwrap() + "\n";
+    if std::env::var_os("UPDATE_OPENAPI").is_some() {
+        std::fs::write(snapshot_path(), &generated).unwrap();
+    }
+    let snapshot = std::fs::read_to_string(snapshot_path()).unwrap_or_default();
+    assert!(
+        snapshot == generated,
+        "the API changed, check the changes and update openapi.json by running the tests with \
+         UPDATE_OPENAPI=1"
+    );
+}
+
+#[tokio::test]
+async fn document_is_served() {
+    let resp = warp::test::request()
+        .path("/openapi.json")
+        .reply(&create_routes())
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let served: Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(served, document());
+}
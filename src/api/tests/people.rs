@@ -0,0 +1,157 @@
+use super::create_routes;
+use serde_json::{json, Value};
+use warp::http::StatusCode;
+
+async fn get(path: &str) -> (StatusCode, Value) {
+    let resp = warp::test::request()
+        .path(path)
+        .reply(&create_routes())
+        .await;
+    (resp.status(), serde_json::from_slice(resp.body()).unwrap())
+}
+
+fn names(people: &Value) -> Vec<&str> {
+    people
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|person| person["name"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn lists_people_with_filters() {
+    let (status, body) = get("/people").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        names(&body),
+        vec![
+            "Daughter House",
+            "Father House",
+            "Grandson Other",
+            "Husband Other",
+            "Mother House",
+            "Son House"
+        ]
+    );
+
+    let (_, body) = get("/people?house=House&sex=M").await;
+    assert_eq!(names(&body), vec!["Father House", "Son House"]);
+
+    let (_, body) = get("/people?house=Other&alive=false").await;
+    assert_eq!(body, json!([]));
+
+    let (status, body) = get("/people?alive=maybe").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "INVALID_QUERY");
+}
+
+#[tokio::test]
+async fn gets_person_by_name() {
+    let (status, body) = get("/people/Daughter%20House").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["name"], "Daughter House");
+    assert_eq!(body["father"], "Father House");
+    assert_eq!(body["sons"], json!(["Grandson Other"]));
+
+    let (status, body) = get("/people/Nobody%20House").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "PERSON_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn gets_relatives() {
+    let (status, body) = get("/people/Son%20House/relatives").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body["person"],
+        json!({"id": "son-house", "name": "Son House", "house": "House", "sex": "M", "alive": true})
+    );
+    assert_eq!(body["father"]["name"], "Father House");
+    assert_eq!(body["mother"]["name"], "Mother House");
+    assert_eq!(body["sons"], json!([]));
+    assert_eq!(names(&body["brothers"]), Vec::<&str>::new());
+    assert_eq!(names(&body["sisters"]), vec!["Daughter House"]);
+    assert_eq!(names(&body["nephews"]), vec!["Grandson Other"]);
+    assert_eq!(body["nieces"], json!([]));
+    assert_eq!(body["grandparents"], json!([]));
+    assert_eq!(body["siblings"][0]["kind"], "full");
+    assert_eq!(body["siblings"][0]["person"]["name"], "Daughter House");
+
+    let (_, body) = get("/people/Grandson%20Other/relatives").await;
+    assert_eq!(
+        names(&body["grandparents"]),
+        vec!["Father House", "Mother House"]
+    );
+    assert_eq!(names(&body["uncles"]), vec!["Son House"]);
+    assert_eq!(body["aunts"], json!([]));
+    assert_eq!(body["cousins"], json!([]));
+
+    let (status, _) = get("/people/Nobody%20House/relatives").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+fn generations(people: &Value) -> Vec<(&str, u64)> {
+    people
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|member| {
+            (
+                member["person"]["name"].as_str().unwrap(),
+                member["generation"].as_u64().unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn gets_ancestors() {
+    let (status, body) = get("/people/Grandson%20Other/ancestors").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        generations(&body),
+        vec![
+            ("Husband Other", 1),
+            ("Daughter House", 1),
+            ("Father House", 2),
+            ("Mother House", 2)
+        ]
+    );
+    assert_eq!(body[0]["person"]["id"], "husband-other");
+
+    let (_, body) = get("/people/Grandson%20Other/ancestors?max_depth=1").await;
+    assert_eq!(
+        generations(&body),
+        vec![("Husband Other", 1), ("Daughter House", 1)]
+    );
+
+    let (status, body) = get("/people/Grandson%20Other/ancestors?max_depth=-1").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "INVALID_QUERY");
+    let (status, _) = get("/people/Nobody%20House/ancestors").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn gets_descendants_with_filters() {
+    let (status, body) = get("/people/Mother%20House/descendants").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        generations(&body),
+        vec![
+            ("Son House", 1),
+            ("Daughter House", 1),
+            ("Grandson Other", 2)
+        ]
+    );
+
+    let (_, body) = get("/people/Mother%20House/descendants?max_depth=1&sex=F").await;
+    assert_eq!(generations(&body), vec![("Daughter House", 1)]);
+
+    let (_, body) = get("/people/Mother%20House/descendants?house=Other&alive=true").await;
+    assert_eq!(generations(&body), vec![("Grandson Other", 2)]);
+
+    let (status, _) = get("/people/Nobody%20House/descendants").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
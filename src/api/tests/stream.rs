@@ -0,0 +1,53 @@
+use super::create_lineage_and_changes;
+use crate::lineage::SuccessionChange;
+use tokio::sync::broadcast::TryRecvError;
+use warp::http::StatusCode;
+
+fn change(person: &str, old: Option<&str>, new: Option<&str>) -> SuccessionChange {
+    SuccessionChange {
+        person: person.to_string(),
+        old_successor: old.map(str::to_string),
+        new_successor: new.map(str::to_string),
+    }
+}
+
+#[tokio::test]
+async fn kill_publishes_heir_changes() {
+    let (lineage, changes) = create_lineage_and_changes();
+    let mut subscriber = changes.subscribe();
+    changes
+        .kill(&mut *lineage.write().await, "Son House")
+        .unwrap();
+    let mut published = vec![];
+    while let Ok(change) = subscriber.try_recv() {
+        published.push(change);
+    }
+    published.sort_by(|a, b| a.person.cmp(&b.person));
+    assert_eq!(
+        published,
+        vec![
+            change("Father House", Some("Son House"), Some("Daughter House")),
+            change("Mother House", Some("Son House"), Some("Daughter House")),
+        ]
+    );
+
+    // failed changes publish nothing
+    assert!(changes
+        .kill(&mut *lineage.write().await, "Son House")
+        .is_err());
+    assert_eq!(subscriber.try_recv().unwrap_err(), TryRecvError::Empty);
+}
+
+#[tokio::test]
+async fn stream_is_server_sent_events() {
+    let (lineage, changes) = create_lineage_and_changes();
+    let routes = super::super::routes(lineage, changes);
+    // the stream never ends, only the headers can be checked
+    let filter = warp::test::request().path("/successions/stream");
+    let resp =
+        tokio::time::timeout(std::time::Duration::from_millis(100), filter.reply(&routes)).await;
+    if let Ok(resp) = resp {
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()["content-type"], "text/event-stream");
+    }
+}
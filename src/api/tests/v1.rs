@@ -0,0 +1,86 @@
+use super::create_routes;
+use warp::http::StatusCode;
+
+#[tokio::test]
+async fn successor_is_plain_text() {
+    let routes = create_routes();
+    let resp = warp::test::request()
+        .path("/successor?name=Father%20House")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.body(), "Son House");
+
+    let resp = warp::test::request()
+        .path("/successor?name=Nobody%20House")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn successor_of_someone_without_heir_is_not_a_missing_person() {
+    let routes = create_routes();
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/kill?name=Husband%20Other")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = warp::test::request()
+        .path("/successor?name=Grandson%20Other")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let body: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(body["code"], "NO_SUCCESSOR");
+    assert_eq!(body["message"], "Grandson Other has no living heir");
+}
+
+#[tokio::test]
+async fn kill_changes_successor() {
+    let routes = create_routes();
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/kill?name=Son%20House")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.body(), "Killed Son House successfully");
+
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/kill?name=Son%20House")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.body(), "Son House was already dead");
+
+    let resp = warp::test::request()
+        .path("/successor?name=Father%20House")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.body(), "Daughter House");
+}
+
+#[tokio::test]
+async fn names_are_resolved() {
+    let routes = create_routes();
+    for name in &["father%20house", "Father%20House%20", "FATHER%20%20HOUSE"] {
+        let resp = warp::test::request()
+            .path(&format!("/successor?name={}", name))
+            .reply(&routes)
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK, "{}", name);
+        assert_eq!(resp.body(), "Son House");
+    }
+
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/kill?name=sonny")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.body(), "Killed Son House successfully");
+}
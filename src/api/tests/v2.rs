@@ -0,0 +1,110 @@
+use super::create_routes;
+use serde_json::{json, Value};
+use warp::http::StatusCode;
+
+fn body_json(resp: &warp::http::Response<warp::hyper::body::Bytes>) -> Value {
+    serde_json::from_slice(resp.body()).unwrap()
+}
+
+#[tokio::test]
+async fn successor_is_json_with_tier_and_kinship_path() {
+    let routes = create_routes();
+    let resp = warp::test::request()
+        .path("/v2/successor?name=Son%20House")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "application/json");
+    let body = body_json(&resp);
+    assert_eq!(body["law"], "male-preference");
+    assert_eq!(body["tier"], "nephews");
+    assert_eq!(body["person"]["name"], "Son House");
+    assert_eq!(
+        body["successor"],
+        json!({
+            "id": "grandson-other",
+            "name": "Grandson Other",
+            "house": "Other",
+            "sex": "M",
+            "alive": true,
+            "father": "Husband Other",
+            "mother": "Daughter House",
+            "sons": [],
+            "daughters": [],
+        })
+    );
+    let path: Vec<&Value> = body["kinship_path"].as_array().unwrap().iter().collect();
+    assert_eq!(path.len(), 4);
+    assert_eq!(*path[0], json!({"name": "Son House", "relation": null}));
+    assert_eq!(path[1]["relation"], "father");
+    assert_eq!(
+        path[2],
+        &json!({"name": "Daughter House", "relation": "daughter"})
+    );
+    assert_eq!(
+        path[3],
+        &json!({"name": "Grandson Other", "relation": "son"})
+    );
+}
+
+#[tokio::test]
+async fn successor_is_null_when_nobody_can_inherit() {
+    let routes = create_routes();
+    let resp = warp::test::request()
+        .path("/v2/successor?name=Grandson%20Other")
+        .reply(&routes)
+        .await;
+    let body = body_json(&resp);
+    assert_eq!(body["successor"]["name"], "Husband Other");
+    assert_eq!(body["tier"], "house");
+    assert_eq!(body["kinship_path"][1]["relation"], "father");
+
+    warp::test::request()
+        .method("POST")
+        .path("/v2/kill?name=Husband%20Other")
+        .reply(&routes)
+        .await;
+    let resp = warp::test::request()
+        .path("/v2/successor?name=Grandson%20Other")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = body_json(&resp);
+    assert_eq!(body["successor"], Value::Null);
+    assert_eq!(body["tier"], Value::Null);
+}
+
+#[tokio::test]
+async fn kill_returns_person_record() {
+    let routes = create_routes();
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/v2/kill?name=Son%20House")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = body_json(&resp);
+    assert_eq!(body["killed"]["name"], "Son House");
+    assert_eq!(body["killed"]["alive"], false);
+    assert_eq!(
+        body["succession_changes"],
+        json!([
+            {"person": "Father House", "old_successor": "Son House", "new_successor": "Daughter House"},
+            {"person": "Mother House", "old_successor": "Son House", "new_successor": "Daughter House"},
+        ])
+    );
+
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/v2/kill?name=Son%20House")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    let resp = warp::test::request()
+        .method("POST")
+        .path("/v2/kill?name=Nobody%20House")
+        .reply(&routes)
+        .await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,105 @@
+use super::changes::ChangeLog;
+use super::error::ApiError;
+use super::openapi::{Endpoint, Response};
+use super::NameQueryParam;
+use crate::lineage::{KillError, Lineage};
+use async_std::sync::{Arc, RwLock};
+use std::convert::Infallible;
+use warp::{Filter, Reply};
+
+/// GET /successor?name={name} => 200 OK with body "{name} successor"
+pub fn get_successor_route(
+    lineage_ref: Arc<RwLock<Lineage>>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    // matching the path first so unmatched paths are not reported as a wrong method
+    warp::path!("successor") // only matching successor path
+        .and(warp::get()) // only get requests
+        .and(warp::query::<NameQueryParam>()) // only having a name query parameter
+        .and(warp::any().map(move || lineage_ref.clone()))
+        .and_then(get_successor)
+}
+
+/// This cant be inline because async closures are unstable for now, needs to be a standalone function
+async fn get_successor(
+    query: NameQueryParam,
+    lineage: Arc<RwLock<Lineage>>,
+) -> Result<impl warp::Reply, Infallible> {
+    let lineage = lineage.read().await;
+    Ok(match lineage.resolve(&query.name) {
+        Err(e) => ApiError::unresolved(&lineage, e).into_response(),
+        Ok(person) => match lineage.next_in_line(person.id()) {
+            None => ApiError::no_successor(person.name()).into_response(),
+            Some(successor) => successor.name().to_string().into_response(),
+        },
+    })
+}
+
+/// POST /kill?name={name} => 200 OK with body "Killed {name} successfully"
+pub fn kill_person_route(
+    post_lineage_ref: Arc<RwLock<Lineage>>,
+    changes: Arc<ChangeLog>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("kill") // only matching kill path
+        .and(warp::post()) // only post requests
+        .and(warp::query::<NameQueryParam>()) // only having a name query parameter
+        .and(warp::any().map(move || post_lineage_ref.clone()))
+        .and(warp::any().map(move || changes.clone()))
+        .and_then(kill_person)
+}
+
+/// This cant be inline because async closures are unstable for now, needs to be a standalone function
+async fn kill_person(
+    query: NameQueryParam,
+    lineage: Arc<RwLock<Lineage>>,
+    changes: Arc<ChangeLog>,
+) -> Result<impl warp::Reply, Infallible> {
+    let mut lineage = lineage.write().await;
+    let (id, name) = match lineage.resolve(&query.name) {
+        Err(e) => return Ok(ApiError::unresolved(&lineage, e).into_response()),
+        Ok(person) => (person.id().to_string(), person.name().to_string()),
+    };
+    Ok(match changes.kill(&mut lineage, &id) {
+        Ok(_) => format!("Killed {} successfully", name).into_response(),
+        Err(e) => match e {
+            KillError::PersonNotFound => ApiError::person_not_found(&name).into_response(),
+            // kept as a success for old clients, v2 answers with an ALREADY_DEAD error
+            KillError::PersonAlreadyDead => format!("{} was already dead", name).into_response(),
+        },
+    })
+}
+
+pub fn endpoints() -> Vec<Endpoint> {
+    vec![
+        Endpoint::new(
+            "get",
+            "/successor",
+            "Name of the heir of the person, as plain text",
+        )
+        .query::<String>(
+            "name",
+            true,
+            "Id or name of the person, case insensitive, or one of its aliases",
+            "Son House",
+        )
+        .response(Response::text(200, "Name of the heir"))
+        .response(Response::error(404, "PERSON_NOT_FOUND or NO_SUCCESSOR"))
+        .response(Response::error(409, "AMBIGUOUS_NAME")),
+        Endpoint::new(
+            "post",
+            "/kill",
+            "Kills the person, answering with plain text",
+        )
+        .query::<String>(
+            "name",
+            true,
+            "Id or name of the person, case insensitive, or one of its aliases",
+            "Son House",
+        )
+        .response(Response::text(
+            200,
+            "Killed {name} successfully or {name} was already dead",
+        ))
+        .response(Response::error(404, "PERSON_NOT_FOUND"))
+        .response(Response::error(409, "AMBIGUOUS_NAME")),
+    ]
+}
@@ -0,0 +1,108 @@
+use super::changes::ChangeLog;
+use super::error::ApiError;
+use super::model::{KillResponse, PersonView, SuccessorResponse};
+use super::openapi::{Endpoint, Response};
+use super::NameQueryParam;
+use crate::lineage::{KillError, Lineage};
+use async_std::sync::{Arc, RwLock};
+use std::convert::Infallible;
+use warp::{Filter, Reply};
+
+/// GET /v2/successor?name={name} => 200 OK with a JSON `SuccessorResponse`
+pub fn get_successor_route(
+    lineage_ref: Arc<RwLock<Lineage>>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("successor")
+        .and(warp::get())
+        .and(warp::query::<NameQueryParam>())
+        .and(warp::any().map(move || lineage_ref.clone()))
+        .and_then(get_successor)
+}
+
+async fn get_successor(
+    query: NameQueryParam,
+    lineage: Arc<RwLock<Lineage>>,
+) -> Result<impl warp::Reply, Infallible> {
+    let lineage = lineage.read().await;
+    Ok(match lineage.resolve(&query.name) {
+        Err(e) => ApiError::unresolved(&lineage, e).into_response(),
+        Ok(person) => {
+            let law = lineage.succession_law();
+            let succession = lineage.succession_of(person, law);
+            warp::reply::json(&SuccessorResponse::new(&lineage, person, law, succession))
+                .into_response()
+        }
+    })
+}
+
+/// POST /v2/kill?name={name} => 200 OK with a JSON `KillResponse`
+pub fn kill_person_route(
+    lineage_ref: Arc<RwLock<Lineage>>,
+    changes: Arc<ChangeLog>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("kill")
+        .and(warp::post())
+        .and(warp::query::<NameQueryParam>())
+        .and(warp::any().map(move || lineage_ref.clone()))
+        .and(warp::any().map(move || changes.clone()))
+        .and_then(kill_person)
+}
+
+async fn kill_person(
+    query: NameQueryParam,
+    lineage: Arc<RwLock<Lineage>>,
+    changes: Arc<ChangeLog>,
+) -> Result<impl warp::Reply, Infallible> {
+    let mut lineage = lineage.write().await;
+    let (id, name) = match lineage.resolve(&query.name) {
+        Err(e) => return Ok(ApiError::unresolved(&lineage, e).into_response()),
+        Ok(person) => (person.id().to_string(), person.name().to_string()),
+    };
+    Ok(match changes.kill(&mut lineage, &id) {
+        Ok(succession_changes) => {
+            let killed = lineage.get_from_id(&id).expect("killed person must exist");
+            warp::reply::json(&KillResponse {
+                killed: PersonView::new(&lineage, killed),
+                succession_changes,
+            })
+            .into_response()
+        }
+        Err(KillError::PersonNotFound) => ApiError::person_not_found(&name).into_response(),
+        Err(KillError::PersonAlreadyDead) => ApiError::already_dead(&name).into_response(),
+    })
+}
+
+pub fn endpoints() -> Vec<Endpoint> {
+    vec![
+        Endpoint::new(
+            "get",
+            "/v2/successor",
+            "Record of the person and of its heir",
+        )
+        .query::<String>(
+            "name",
+            true,
+            "Id or name of the person, case insensitive, or one of its aliases",
+            "Son House",
+        )
+        .response(Response::json::<SuccessorResponse>(
+            200,
+            "The person and its heir",
+        ))
+        .response(Response::error(404, "PERSON_NOT_FOUND"))
+        .response(Response::error(409, "AMBIGUOUS_NAME")),
+        Endpoint::new("post", "/v2/kill", "Kills the person")
+            .query::<String>(
+                "name",
+                true,
+                "Id or name of the person, case insensitive, or one of its aliases",
+                "Son House",
+            )
+            .response(Response::json::<KillResponse>(
+                200,
+                "The killed person and whose heir changed",
+            ))
+            .response(Response::error(404, "PERSON_NOT_FOUND"))
+            .response(Response::error(409, "AMBIGUOUS_NAME or ALREADY_DEAD")),
+    ]
+}
@@ -0,0 +1,271 @@
+//! Commands of the binary other than serving the API, working on the lineage directly so scripts
+//! do not need a running server. Each returns what to print, or why it failed.
+use crate::config::{GraphArgs, GraphFormat};
+use crate::dataset;
+use crate::lineage::{GraphOptions, Lineage, PersonFilter, SuccessionChange};
+use crate::persistence::{Event, Journal};
+use std::path::{Path, PathBuf};
+
+/// The name of the heir of the person under the lineage law
+pub fn successor(lineage: &Lineage, name: &str) -> Result<String, String> {
+    let person = lineage.resolve(name).map_err(|e| e.to_string())?;
+    lineage
+        .succession_of(person, lineage.succession_law())
+        .map(|succession| succession.successor.name().to_string())
+        .ok_or_else(|| format!("{} has no heir", person.name()))
+}
+
+/// The heirs of the person in order, one numbered line each, `count` of them at most
+pub fn line(lineage: &Lineage, name: &str, count: Option<usize>) -> Result<String, String> {
+    let person = lineage.resolve(name).map_err(|e| e.to_string())?;
+    let line = lineage.line_of_succession(person, lineage.succession_law(), count);
+    if line.is_empty() {
+        return Err(format!("{} has no heir", person.name()));
+    }
+    Ok(line
+        .iter()
+        .enumerate()
+        .map(|(i, succession)| format!("{}. {}", i + 1, succession.successor.name()))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Kills the people, all of them or none if one can not be found, recording it in the journal.
+/// Tells who was killed, who was already dead and whose heir changed. Without a journal saving to
+/// a file the deaths only last as long as the command, which is warned about on stderr.
+pub fn kill(lineage: &mut Lineage, journal: &Journal, names: &[String]) -> Result<String, String> {
+    let people = names
+        .iter()
+        .map(|name| {
+            lineage
+                .resolve(name)
+                .map(|person| (person.id().to_string(), person.name().to_string()))
+                .map_err(|e| e.to_string())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let ids: Vec<&str> = people.iter().map(|(id, _)| id.as_str()).collect();
+    let (outcomes, changes) = lineage.kill_batch(&ids).expect("the people were resolved");
+    if !journal.is_enabled() {
+        eprintln!("No --state-file, the deaths are not saved");
+    }
+    let mut lines = vec![];
+    for ((id, name), outcome) in people.iter().zip(outcomes) {
+        if outcome.is_ok() {
+            journal
+                .record(&Event::Kill { id: id.clone() })
+                .map_err(|e| format!("could not persist the death of {}: {}", name, e))?;
+            lines.push(format!("Killed {}", name));
+        } else {
+            lines.push(format!("{} is already dead", name));
+        }
+    }
+    lines.extend(changes.iter().map(describe_change));
+    Ok(lines.join("\n"))
+}
+
+/// "Eddard Stark: Robb Stark → Bran Stark"
+pub fn describe_change(change: &SuccessionChange) -> String {
+    format!(
+        "{}: {} → {}",
+        change.person,
+        change.old_successor.as_deref().unwrap_or("nobody"),
+        change.new_successor.as_deref().unwrap_or("nobody")
+    )
+}
+
+/// The lineage drawn in the format of the arguments, houses grouped and the dead greyed out
+pub fn graph(lineage: &Lineage, args: &GraphArgs) -> Result<String, String> {
+    let subtree_of = match &args.subtree {
+        Some(name) => Some(lineage.resolve(name).map_err(|e| e.to_string())?),
+        None => None,
+    };
+    let options = GraphOptions {
+        house_clusters: true,
+        grey_out_dead: true,
+        subtree_of,
+        filter: PersonFilter {
+            alive: if args.alive_only { Some(true) } else { None },
+            house: args.house.clone(),
+            ..PersonFilter::default()
+        },
+        ..GraphOptions::default()
+    };
+    Ok(match args.format {
+        GraphFormat::Dot => lineage.to_graphviz_with(&options),
+        GraphFormat::Svg => lineage.to_svg(&options),
+        GraphFormat::Mermaid => lineage.to_mermaid(&options),
+        GraphFormat::PlantUml => lineage.to_plantuml(&options),
+        GraphFormat::Text => lineage.to_text_tree(
+            subtree_of.ok_or("the text tree needs the --subtree to draw")?,
+            None,
+        ),
+    })
+}
+
+/// Every problem of the datasets and aliases files as the error, one per line
+pub fn lint(datasets: &[PathBuf], aliases: &[PathBuf]) -> Result<String, String> {
+    let problems = dataset::lint(datasets, aliases);
+    if problems.is_empty() {
+        Ok("No problem found".to_string())
+    } else {
+        Err(problems.join("\n"))
+    }
+}
+
+/// Loads the input dataset and writes it in the format of the output extension
+pub fn convert(input: &Path, output: &Path) -> Result<String, String> {
+    let lineage = dataset::read_lineage(&[input.to_path_buf()])?;
+    let extension = output
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    let diagram = match extension.as_str() {
+        "csv" | "json" => {
+            let relationships = lineage.relationships();
+            dataset::write_records(output, &relationships)?;
+            return Ok(format!(
+                "Wrote {} relationships to {}",
+                relationships.len(),
+                output.display()
+            ));
+        }
+        "dot" | "gv" => lineage.to_graphviz(),
+        "svg" => lineage.to_svg(&GraphOptions::default()),
+        "mmd" => lineage.to_mermaid(&GraphOptions::default()),
+        "puml" => lineage.to_plantuml(&GraphOptions::default()),
+        _ => {
+            return Err(format!(
+                "unknown format of {}, expected the csv, json, dot, svg, mmd or puml extension",
+                output.display()
+            ))
+        }
+    };
+    std::fs::write(output, diagram)
+        .map_err(|e| format!("could not write {}: {}", output.display(), e))?;
+    Ok(format!("Wrote {}", output.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lineage::{
+        ParentChildInfo,
+        Sex::{Female, Male},
+    };
+
+    /// A father with a son and a daughter
+    fn lineage() -> Lineage {
+        let mut lineage = Lineage::new();
+        lineage.insert(ParentChildInfo::new(
+            "Father House",
+            Male,
+            "Son House",
+            Male,
+        ));
+        lineage.insert(ParentChildInfo::new(
+            "Father House",
+            Male,
+            "Daughter House",
+            Female,
+        ));
+        lineage
+    }
+
+    #[test]
+    fn prints_the_heirs() {
+        let lineage = lineage();
+        assert_eq!(successor(&lineage, "Father House").unwrap(), "Son House");
+        assert_eq!(
+            line(&lineage, "Father House", None).unwrap(),
+            "1. Son House\n2. Daughter House"
+        );
+        assert_eq!(
+            line(&lineage, "Father House", Some(1)).unwrap(),
+            "1. Son House"
+        );
+        assert!(successor(&lineage, "Nobody").is_err());
+    }
+
+    #[test]
+    fn kills_all_the_people_or_none() {
+        let names = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>()
+        };
+        // the deaths are applied even without saving them
+        let mut unsaved = lineage();
+        assert_eq!(
+            kill(&mut unsaved, &Journal::disabled(), &names(&["Son House"])).unwrap(),
+            "Killed Son House\n\
+             Daughter House: Son House → Father House\n\
+             Father House: Son House → Daughter House"
+        );
+        assert!(!unsaved.get_from_name("Son House").unwrap().alive());
+
+        let mut lineage = lineage();
+        let state_file = std::env::temp_dir().join(format!("kill-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&state_file);
+        let journal = Journal::open(&state_file, &mut lineage).unwrap();
+        assert!(kill(&mut lineage, &journal, &names(&["Son House", "Nobody"])).is_err());
+        assert!(lineage.get_from_name("Son House").unwrap().alive());
+
+        let output = kill(&mut lineage, &journal, &names(&["Son House", "Son House"])).unwrap();
+        assert_eq!(
+            output,
+            "Killed Son House\n\
+             Son House is already dead\n\
+             Daughter House: Son House → Father House\n\
+             Father House: Son House → Daughter House"
+        );
+        assert_eq!(
+            successor(&lineage, "Father House").unwrap(),
+            "Daughter House"
+        );
+        assert_eq!(
+            std::fs::read_to_string(&state_file)
+                .unwrap()
+                .lines()
+                .count(),
+            1
+        );
+        std::fs::remove_file(&state_file).unwrap();
+    }
+
+    #[test]
+    fn text_graph_needs_a_subtree() {
+        let lineage = lineage();
+        let mut args = GraphArgs {
+            format: GraphFormat::Text,
+            subtree: None,
+            house: None,
+            alive_only: false,
+        };
+        assert!(graph(&lineage, &args).is_err());
+        args.subtree = Some("Father House".to_string());
+        assert!(graph(&lineage, &args)
+            .unwrap()
+            .starts_with("Father House\n├── Son House ← heir\n"));
+    }
+
+    #[test]
+    fn converts_by_extension() {
+        let dir = std::env::temp_dir();
+        let input = dir.join(format!("convert-{}.csv", std::process::id()));
+        let output = dir.join(format!("convert-{}.mmd", std::process::id()));
+        std::fs::write(
+            &input,
+            "parent_name,parent_sex,child_name,child_sex\nFather House,M,Son House,M\n",
+        )
+        .unwrap();
+        convert(&input, &output).unwrap();
+        assert!(std::fs::read_to_string(&output)
+            .unwrap()
+            .starts_with("flowchart TD\n"));
+        assert!(convert(&input, &dir.join("convert.txt")).is_err());
+        std::fs::remove_file(&input).unwrap();
+        std::fs::remove_file(&output).unwrap();
+    }
+}
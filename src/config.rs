@@ -0,0 +1,363 @@
+use crate::lineage::SuccessionLaw;
+use log::LevelFilter;
+use serde::Deserialize;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::str::FromStr;
+use structopt::StructOpt;
+
+const DEFAULT_DATASET: &str = "got_families.csv";
+const DEFAULT_ALIASES: &str = "got_aliases.csv";
+const DEFAULT_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+const DEFAULT_PORT: u16 = 3030;
+const DEFAULT_WORKERS: usize = 1;
+
+/// Command line arguments. Every option can also be set in the config file, the command line
+/// taking precedence over it.
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Tells Westerosi Maesters who is next in line")]
+pub struct CliArgs {
+    /// TOML config file
+    #[structopt(short, long, parse(from_os_str))]
+    pub config: Option<PathBuf>,
+
+    /// CSV or JSON dataset to load, can be repeated to merge several datasets
+    #[structopt(short, long = "dataset", number_of_values = 1, parse(from_os_str))]
+    pub datasets: Vec<PathBuf>,
+
+    /// CSV file of other names of people ("Ned" for Eddard Stark), can be repeated
+    #[structopt(long = "aliases", number_of_values = 1, parse(from_os_str))]
+    pub aliases: Vec<PathBuf>,
+
+    /// Address to bind the server to
+    #[structopt(short, long)]
+    pub address: Option<IpAddr>,
+
+    /// Port to bind the server to
+    #[structopt(short, long)]
+    pub port: Option<u16>,
+
+    /// Number of worker threads serving requests
+    #[structopt(short, long)]
+    pub workers: Option<usize>,
+
+    /// File where changes (killing someone) are persisted and replayed from at startup
+    #[structopt(long, parse(from_os_str))]
+    pub state_file: Option<PathBuf>,
+
+    /// Log level: off, error, warn, info, debug or trace
+    #[structopt(long)]
+    pub log_level: Option<LevelFilter>,
+
+    /// Succession law used when none is requested: male-preference, absolute-primogeniture or
+    /// full-blood-preference
+    #[structopt(long)]
+    pub succession_law: Option<SuccessionLaw>,
+
+    #[structopt(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// What the binary does with the lineage, starting the server when none is given. Other commands
+/// work on the datasets and the state file directly, without going through HTTP.
+#[derive(Debug, Clone, Default, PartialEq, StructOpt)]
+pub enum Command {
+    /// Starts the web server
+    #[default]
+    Serve,
+    /// Prints the heir of a person
+    Successor {
+        /// Id or name of the person
+        name: String,
+    },
+    /// Prints the heirs of a person in order: its heir, who would inherit if the heir died, and
+    /// so on
+    Line {
+        /// Id or name of the person
+        name: String,
+        /// Number of heirs to print, all of them by default
+        #[structopt(short = "n", long)]
+        count: Option<usize>,
+    },
+    /// Kills people and prints whose heir changed, persisting it to the state file if there is
+    /// one
+    Kill {
+        /// Ids or names of the people
+        #[structopt(required = true)]
+        names: Vec<String>,
+    },
+    /// Prints the lineage as a diagram
+    Graph(GraphArgs),
+    /// Checks the datasets and aliases can be loaded, printing every problem found
+    Lint,
+    /// Opens a shell to explore the lineage and kill people to see what happens, without saving
+    /// anything
+    Repl,
+    /// Converts a dataset between CSV and JSON, or to a diagram, the formats being told by the
+    /// file extensions: csv, json, dot, svg, mmd (Mermaid) or puml (PlantUML)
+    Convert {
+        #[structopt(parse(from_os_str))]
+        input: PathBuf,
+        #[structopt(parse(from_os_str))]
+        output: PathBuf,
+    },
+}
+
+/// Arguments of the graph command
+#[derive(Debug, Clone, PartialEq, StructOpt)]
+pub struct GraphArgs {
+    /// dot, svg, mermaid, plantuml or text, the tree of the descendants of --subtree
+    #[structopt(short, long, default_value = "dot")]
+    pub format: GraphFormat,
+
+    /// Only draws this person, by id or name, and its descendants
+    #[structopt(long)]
+    pub subtree: Option<String>,
+
+    /// Only draws the members of this house
+    #[structopt(long)]
+    pub house: Option<String>,
+
+    /// Only draws the living
+    #[structopt(long)]
+    pub alive_only: bool,
+}
+
+/// Languages the lineage can be drawn in from the command line
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GraphFormat {
+    Dot,
+    Svg,
+    Mermaid,
+    PlantUml,
+    Text,
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(GraphFormat::Dot),
+            "svg" => Ok(GraphFormat::Svg),
+            "mermaid" => Ok(GraphFormat::Mermaid),
+            "plantuml" => Ok(GraphFormat::PlantUml),
+            "text" => Ok(GraphFormat::Text),
+            other => Err(format!(
+                "unknown graph format {}, expected dot, svg, mermaid, plantuml or text",
+                other
+            )),
+        }
+    }
+}
+
+/// Contents of the TOML config file, all fields are optional
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileConfig {
+    pub datasets: Vec<PathBuf>,
+    pub aliases: Vec<PathBuf>,
+    pub address: Option<IpAddr>,
+    pub port: Option<u16>,
+    pub workers: Option<usize>,
+    pub state_file: Option<PathBuf>,
+    pub log_level: Option<String>,
+    pub succession_law: Option<SuccessionLaw>,
+}
+
+/// Final configuration after merging the command line, the config file and the defaults
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub datasets: Vec<PathBuf>,
+    pub aliases: Vec<PathBuf>,
+    pub address: IpAddr,
+    pub port: u16,
+    pub workers: usize,
+    pub state_file: Option<PathBuf>,
+    pub log_level: LevelFilter,
+    pub succession_law: SuccessionLaw,
+    pub command: Command,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+    InvalidLogLevel(String),
+    NoWorkers,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, e) => write!(f, "could not read {}: {}", path.display(), e),
+            ConfigError::Parse(path, e) => write!(f, "invalid config {}: {}", path.display(), e),
+            ConfigError::InvalidLogLevel(level) => write!(f, "invalid log level {}", level),
+            ConfigError::NoWorkers => write!(f, "at least one worker thread is needed"),
+        }
+    }
+}
+
+impl Config {
+    /// Builds the configuration from the process arguments and the config file they point to
+    pub fn load() -> Result<Self, ConfigError> {
+        let args = CliArgs::from_args();
+        let file_config = match &args.config {
+            Some(path) => FileConfig::read(path)?,
+            None => FileConfig::default(),
+        };
+        Self::merge(args, file_config)
+    }
+
+    /// Command line values take precedence over the config file ones, which take precedence over
+    /// the defaults
+    pub fn merge(args: CliArgs, file: FileConfig) -> Result<Self, ConfigError> {
+        let datasets = if !args.datasets.is_empty() {
+            args.datasets
+        } else if !file.datasets.is_empty() {
+            file.datasets
+        } else {
+            vec![PathBuf::from(DEFAULT_DATASET)]
+        };
+        let aliases = if !args.aliases.is_empty() {
+            args.aliases
+        } else if !file.aliases.is_empty() {
+            file.aliases
+        } else {
+            vec![PathBuf::from(DEFAULT_ALIASES)]
+        };
+        let log_level = match (args.log_level, file.log_level) {
+            (Some(level), _) => level,
+            (None, Some(level)) => level
+                .parse()
+                .map_err(|_| ConfigError::InvalidLogLevel(level))?,
+            (None, None) => LevelFilter::Info,
+        };
+        let workers = args.workers.or(file.workers).unwrap_or(DEFAULT_WORKERS);
+        if workers == 0 {
+            return Err(ConfigError::NoWorkers);
+        }
+        Ok(Config {
+            datasets,
+            aliases,
+            address: args.address.or(file.address).unwrap_or(DEFAULT_ADDRESS),
+            port: args.port.or(file.port).unwrap_or(DEFAULT_PORT),
+            workers,
+            state_file: args.state_file.or(file.state_file),
+            log_level,
+            succession_law: args
+                .succession_law
+                .or(file.succession_law)
+                .unwrap_or_default(),
+            command: args.command.unwrap_or_default(),
+        })
+    }
+
+    pub fn socket_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.address, self.port)
+    }
+}
+
+impl FileConfig {
+    pub fn read(path: &PathBuf) -> Result<Self, ConfigError> {
+        let contents =
+            std::fs::read_to_string(path).map_err(|e| ConfigError::Io(path.clone(), e))?;
+        toml::from_str(&contents).map_err(|e| ConfigError::Parse(path.clone(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(cli: &[&str]) -> CliArgs {
+        CliArgs::from_iter(std::iter::once("craft-ai-project").chain(cli.iter().cloned()))
+    }
+
+    #[test]
+    fn defaults_are_used_without_arguments() {
+        let config = Config::merge(args(&[]), FileConfig::default()).unwrap();
+        assert_eq!(config.datasets, vec![PathBuf::from("got_families.csv")]);
+        assert_eq!(config.aliases, vec![PathBuf::from("got_aliases.csv")]);
+        assert_eq!(config.socket_addr(), "127.0.0.1:3030".parse().unwrap());
+        assert_eq!(config.workers, 1);
+        assert_eq!(config.state_file, None);
+        assert_eq!(config.log_level, LevelFilter::Info);
+        assert_eq!(config.succession_law, SuccessionLaw::MalePreference);
+        assert_eq!(config.command, Command::Serve);
+    }
+
+    #[test]
+    fn command_line_overrides_config_file() {
+        let file: FileConfig = toml::from_str(
+            r#"
+            datasets = ["a.csv", "b.csv"]
+            aliases = ["a_aliases.csv"]
+            address = "0.0.0.0"
+            port = 8080
+            workers = 4
+            log_level = "debug"
+            succession_law = "absolute-primogeniture"
+            "#,
+        )
+        .unwrap();
+        let config = Config::merge(args(&["--port", "9000", "-d", "c.csv"]), file).unwrap();
+        assert_eq!(config.datasets, vec![PathBuf::from("c.csv")]);
+        assert_eq!(config.aliases, vec![PathBuf::from("a_aliases.csv")]);
+        assert_eq!(config.socket_addr(), "0.0.0.0:9000".parse().unwrap());
+        assert_eq!(config.workers, 4);
+        assert_eq!(config.log_level, LevelFilter::Debug);
+        assert_eq!(config.succession_law, SuccessionLaw::AbsolutePrimogeniture);
+    }
+
+    #[test]
+    fn commands_take_their_arguments() {
+        let config = Config::merge(
+            args(&[
+                "-d",
+                "c.csv",
+                "graph",
+                "--format",
+                "mermaid",
+                "--subtree",
+                "Eddard Stark",
+                "--alive-only",
+            ]),
+            FileConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(config.datasets, vec![PathBuf::from("c.csv")]);
+        assert_eq!(
+            config.command,
+            Command::Graph(GraphArgs {
+                format: GraphFormat::Mermaid,
+                subtree: Some("Eddard Stark".to_string()),
+                house: None,
+                alive_only: true,
+            })
+        );
+        let config = Config::merge(
+            args(&["line", "Eddard Stark", "-n", "3"]),
+            FileConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            config.command,
+            Command::Line {
+                name: "Eddard Stark".to_string(),
+                count: Some(3),
+            }
+        );
+        assert!(CliArgs::from_iter_safe(&["craft-ai-project", "graph", "-f", "png"]).is_err());
+        assert!(CliArgs::from_iter_safe(&["craft-ai-project", "kill"]).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let file: FileConfig = toml::from_str(r#"log_level = "loud""#).unwrap();
+        assert!(Config::merge(args(&[]), file).is_err());
+        assert!(Config::merge(args(&["--workers", "0"]), FileConfig::default()).is_err());
+        assert!(toml::from_str::<FileConfig>(r#"colour = "red""#).is_err());
+    }
+}
@@ -0,0 +1,341 @@
+//! Datasets the lineage is loaded from: CSV files of parent/child relationships, or JSON arrays of
+//! the same records, and CSV files of aliases
+use crate::lineage::{Lineage, ParentChildInfo, Sex};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Relationship records of a dataset, each with where it is in the file ("got_families.csv:3")
+/// and the record or why it could not be read
+pub type Records<T = ParentChildInfo> = Vec<(String, Result<T, String>)>;
+
+/// Formats of the relationship datasets, told apart by the file extension, CSV being the default
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DatasetFormat {
+    Csv,
+    Json,
+}
+
+impl DatasetFormat {
+    pub fn of(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => DatasetFormat::Json,
+            _ => DatasetFormat::Csv,
+        }
+    }
+}
+
+/// Reads the relationship records of a dataset.
+///
+/// A CSV file should have the following structure
+/// ```text
+///     parent_name, parent_sex, child_name, child_sex
+///     Rickard Stark, M, Eddard Stark, M
+///     Rickard Stark, M, Brandon Stark, M
+/// ```
+/// Optional `parent_id` and `child_id` columns declare the ids of the people, which tell apart
+/// people with the same name. People without an id get one derived from their name.
+/// ```text
+///     parent_name, parent_sex, child_name, child_sex, parent_id, child_id
+///     Eddard Stark, M, Brandon Stark, M, eddard-stark, bran-stark
+/// ```
+/// A JSON file is an array of objects with the same fields.
+pub fn read_records(path: &Path) -> Result<Records, String> {
+    match DatasetFormat::of(path) {
+        DatasetFormat::Csv => read_csv(path),
+        DatasetFormat::Json => {
+            let error = |e: &dyn fmt::Display| format!("could not read {}: {}", path.display(), e);
+            let contents = std::fs::read_to_string(path).map_err(|e| error(&e))?;
+            let values: Vec<serde_json::Value> =
+                serde_json::from_str(&contents).map_err(|e| error(&e))?;
+            Ok(values
+                .into_iter()
+                .enumerate()
+                .map(|(i, value)| {
+                    (
+                        format!("{} record {}", path.display(), i + 1),
+                        serde_json::from_value(value).map_err(|e| e.to_string()),
+                    )
+                })
+                .collect())
+        }
+    }
+}
+
+/// The rows of a CSV file with a header, each with its file and line
+fn read_csv<T: DeserializeOwned>(path: &Path) -> Result<Records<T>, String> {
+    let error = |e: csv::Error| format!("could not read {}: {}", path.display(), e);
+    let mut rdr = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All) // trim leading and trailing whitespace from fields
+        .from_path(path)
+        .map_err(error)?;
+    let headers = rdr.headers().map_err(error)?.clone();
+    let mut rows = vec![];
+    let mut record = csv::StringRecord::new();
+    loop {
+        let location = format!("{}:{}", path.display(), rdr.position().line());
+        match rdr.read_record(&mut record) {
+            Ok(false) => break,
+            Ok(true) => rows.push((
+                location,
+                record
+                    .deserialize(Some(&headers))
+                    .map_err(|e| e.to_string()),
+            )),
+            Err(e) => rows.push((location, Err(e.to_string()))),
+        }
+    }
+    Ok(rows)
+}
+
+/// Merges all the given datasets into a single lineage, failing on the first record which can not
+/// be read or added
+pub fn read_lineage(paths: &[PathBuf]) -> Result<Lineage, String> {
+    let mut lineage = Lineage::new();
+    for path in paths {
+        for (location, record) in read_records(path)? {
+            record
+                .and_then(|record| lineage.load(record).map_err(|e| e.to_string()))
+                .map_err(|e| format!("{}: {}", location, e))?;
+        }
+    }
+    Ok(lineage)
+}
+
+/// Writes relationship records to a CSV or JSON file, depending on its extension
+pub fn write_records(path: &Path, records: &[ParentChildInfo]) -> Result<(), String> {
+    let error = |e: &dyn fmt::Display| format!("could not write {}: {}", path.display(), e);
+    match DatasetFormat::of(path) {
+        DatasetFormat::Csv => {
+            let mut writer = csv::Writer::from_path(path).map_err(|e| error(&e))?;
+            // the id columns are only written when a record needs them, and then for all records
+            let with_ids = records
+                .iter()
+                .any(|record| record.parent_id.is_some() || record.child_id.is_some());
+            let mut columns = vec!["parent_name", "parent_sex", "child_name", "child_sex"];
+            if with_ids {
+                columns.extend(&["parent_id", "child_id"]);
+            }
+            writer.write_record(&columns).map_err(|e| error(&e))?;
+            let sex = |sex: &Sex| match sex {
+                Sex::Male => "M",
+                Sex::Female => "F",
+            };
+            for record in records {
+                let mut row = vec![
+                    record.parent_name.as_str(),
+                    sex(&record.parent_sex),
+                    &record.child_name,
+                    sex(&record.child_sex),
+                ];
+                if with_ids {
+                    row.push(record.parent_id.as_deref().unwrap_or_default());
+                    row.push(record.child_id.as_deref().unwrap_or_default());
+                }
+                writer.write_record(&row).map_err(|e| error(&e))?;
+            }
+            writer.flush().map_err(|e| error(&e))
+        }
+        DatasetFormat::Json => {
+            let json = serde_json::to_string_pretty(records).map_err(|e| error(&e))?;
+            std::fs::write(path, json + "\n").map_err(|e| error(&e))
+        }
+    }
+}
+
+/// Another name of a person of the lineage, see `read_aliases`
+#[derive(Debug, Deserialize)]
+struct AliasEntry {
+    alias: String,
+    name: String,
+}
+
+/// Reads the aliases of a CSV file, `name` being the name or the id of the person, each with
+/// where it is in the file.
+///
+/// The file should have the following structure
+/// ```text
+///     alias, name
+///     Ned, Eddard Stark
+/// ```
+fn read_aliases(path: &Path) -> Result<Records<AliasEntry>, String> {
+    read_csv(path)
+}
+
+/// Adds the aliases of a CSV file to the lineage, see `read_aliases`. Aliases of people who are
+/// not part of it, or of names borne by several people, are skipped with a warning. A missing
+/// file is only warned about, so the default aliases file is not required with other datasets.
+pub fn add_aliases(path: &Path, lineage: &mut Lineage) -> Result<(), String> {
+    if !path.exists() {
+        log::warn!("Could not open aliases {}: no such file", path.display());
+        return Ok(());
+    }
+    for (location, entry) in read_aliases(path)? {
+        let entry = entry.map_err(|e| format!("{}: {}", location, e))?;
+        if lineage.add_alias(&entry.alias, &entry.name).is_err() {
+            log::warn!(
+                "Alias {} of {} skipped, nobody or several people have this name or id",
+                entry.alias,
+                entry.name
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Every problem of the datasets and the aliases files, as "location: problem" lines, empty if
+/// they can all be loaded as they are. Records are added one after the other, so a record
+/// conflicting with a previous one is reported, not the previous one.
+pub fn lint(datasets: &[PathBuf], aliases: &[PathBuf]) -> Vec<String> {
+    let mut problems = vec![];
+    let mut lineage = Lineage::new();
+    for path in datasets {
+        match read_records(path) {
+            Ok(records) => {
+                for (location, record) in records {
+                    let result =
+                        record.and_then(|record| lineage.load(record).map_err(|e| e.to_string()));
+                    if let Err(e) = result {
+                        problems.push(format!("{}: {}", location, e));
+                    }
+                }
+            }
+            Err(e) => problems.push(e),
+        }
+    }
+    // missing aliases files are not needed, as when loading
+    for path in aliases.iter().filter(|path| path.exists()) {
+        match read_aliases(path) {
+            Ok(entries) => {
+                for (location, entry) in entries {
+                    let result = entry.and_then(|entry| {
+                        lineage
+                            .add_alias(&entry.alias, &entry.name)
+                            .map_err(|e| format!("alias {}: {}", entry.alias, e))
+                    });
+                    if let Err(e) = result {
+                        problems.push(format!("{}: {}", location, e));
+                    }
+                }
+            }
+            Err(e) => problems.push(e),
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lineage::Sex::{Female, Male};
+    use crate::lineage::SUCCESSIONS_COMPUTED;
+
+    /// A file of the temporary directory unique to the test process, removed first if it exists
+    fn temp_file(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn converts_between_csv_and_json() {
+        let mut records = vec![
+            ParentChildInfo::new("Father House", Male, "Son House", Male),
+            ParentChildInfo::new("Father House", Male, "Daughter House", Female),
+        ];
+        records[1].child_id = Some("daughter".to_string());
+        let csv = temp_file("dataset.csv");
+        let json = temp_file("dataset.json");
+        write_records(&csv, &records).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&csv).unwrap(),
+            "parent_name,parent_sex,child_name,child_sex,parent_id,child_id\n\
+             Father House,M,Son House,M,,\n\
+             Father House,M,Daughter House,F,,daughter\n"
+        );
+
+        let read = |path: &Path| -> Vec<ParentChildInfo> {
+            read_records(path)
+                .unwrap()
+                .into_iter()
+                .map(|(_, record)| record.unwrap())
+                .collect()
+        };
+        let from_csv = read(&csv);
+        write_records(&json, &from_csv).unwrap();
+        assert_eq!(read(&json), from_csv);
+        assert_eq!(
+            read_lineage(std::slice::from_ref(&json)).unwrap().people(),
+            read_lineage(std::slice::from_ref(&csv)).unwrap().people()
+        );
+        std::fs::remove_file(&csv).unwrap();
+        std::fs::remove_file(&json).unwrap();
+    }
+
+    #[test]
+    fn lint_reports_every_problem_with_its_location() {
+        let dataset = temp_file("lint.csv");
+        let aliases = temp_file("lint-aliases.csv");
+        std::fs::write(
+            &dataset,
+            "parent_name,parent_sex,child_name,child_sex\n\
+             Father House,M,Son House,M\n\
+             Father House,X,Daughter House,F\n\
+             Son House,M,Father House,M\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &aliases,
+            "alias,name\nDad,Father House\nGhost,Nobody Here\n",
+        )
+        .unwrap();
+
+        let problems = lint(
+            std::slice::from_ref(&dataset),
+            std::slice::from_ref(&aliases),
+        );
+        assert_eq!(problems.len(), 3, "{:?}", problems);
+        assert!(problems[0].starts_with(&format!("{}:3: ", dataset.display())));
+        assert!(problems[1].starts_with(&format!("{}:4: ", dataset.display())));
+        assert!(problems[2].starts_with(&format!("{}:3: alias Ghost", aliases.display())));
+        // the first problem also stops the loading
+        assert!(read_lineage(std::slice::from_ref(&dataset))
+            .unwrap_err()
+            .starts_with(&format!("{}:3: ", dataset.display())));
+
+        std::fs::remove_file(&dataset).unwrap();
+        std::fs::remove_file(&aliases).unwrap();
+        assert!(lint(&[dataset], &[aliases])[0].contains("could not read"));
+    }
+
+    #[test]
+    fn loads_without_computing_successions() {
+        // a binary tree of men of the same house, each of them an heir dependent of the others
+        let records: Vec<ParentChildInfo> = (1..1000)
+            .map(|i| {
+                ParentChildInfo::new(
+                    format!("Man{} House", (i - 1) / 2),
+                    Male,
+                    format!("Man{} House", i),
+                    Male,
+                )
+            })
+            .collect();
+        let dataset = temp_file("big-house.csv");
+        write_records(&dataset, &records).unwrap();
+
+        let computed = || SUCCESSIONS_COMPUTED.with(|count| count.get());
+        let before = computed();
+        assert_eq!(
+            read_lineage(std::slice::from_ref(&dataset))
+                .unwrap()
+                .people()
+                .len(),
+            records.len() + 1
+        );
+        assert!(lint(std::slice::from_ref(&dataset), &[]).is_empty());
+        assert_eq!(computed(), before);
+        std::fs::remove_file(&dataset).unwrap();
+    }
+}
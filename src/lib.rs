@@ -0,0 +1,10 @@
+//! Lines of succession of the Westerosi noble houses, see the README for the succession rules.
+//! The `lineage` module holds the family graph and the succession logic, `api` serves it over
+//! HTTP.
+pub mod api;
+pub mod cli;
+pub mod config;
+pub mod dataset;
+pub mod lineage;
+pub mod persistence;
+pub mod repl;
@@ -0,0 +1,156 @@
+//! Ancestors and descendants of a person over several generations. The traversals keep their own
+//! queue instead of recursing, so the depth of a lineage is only bounded by memory.
+use super::{Lineage, Person, Sex};
+use std::collections::{HashSet, VecDeque};
+
+/// Criteria people must all match, None matches everyone
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PersonFilter {
+    pub alive: Option<bool>,
+    pub sex: Option<Sex>,
+    pub house: Option<String>,
+}
+
+impl PersonFilter {
+    pub fn matches(&self, person: &Person) -> bool {
+        self.alive.is_none_or(|alive| person.alive == alive)
+            && self.sex.as_ref().is_none_or(|sex| person.sex == *sex)
+            && self
+                .house
+                .as_deref()
+                .is_none_or(|house| person.house == house)
+    }
+}
+
+/// Which relatives of a person a `Generations` iterator goes to
+#[derive(Debug, Clone, Copy)]
+enum Direction {
+    Parents,
+    Children,
+}
+
+/// Iterator over the ancestors or the descendants of a person, generation by generation, with the
+/// number of generations separating them from the person. Someone reachable through several lines,
+/// as with marriages between cousins, is only returned once, at the closest generation.
+pub struct Generations<'a> {
+    lineage: &'a Lineage,
+    direction: Direction,
+    max_depth: Option<usize>,
+    filter: PersonFilter,
+    queue: VecDeque<(usize, usize)>,
+    visited: HashSet<usize>,
+}
+
+impl<'a> Generations<'a> {
+    fn new(
+        lineage: &'a Lineage,
+        person: &Person,
+        direction: Direction,
+        max_depth: Option<usize>,
+        filter: PersonFilter,
+    ) -> Self {
+        let mut visited = HashSet::new();
+        visited.insert(person.idx);
+        Generations {
+            lineage,
+            direction,
+            max_depth,
+            filter,
+            queue: VecDeque::from(vec![(person.idx, 0)]),
+            visited,
+        }
+    }
+}
+
+impl<'a> Iterator for Generations<'a> {
+    type Item = (&'a Person, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((idx, generation)) = self.queue.pop_front() {
+            let person = &self.lineage.people_graph[idx];
+            if self
+                .max_depth
+                .is_none_or(|max_depth| generation < max_depth)
+            {
+                let relatives: Vec<usize> = match self.direction {
+                    Direction::Parents => person.father.into_iter().chain(person.mother).collect(),
+                    Direction::Children => person
+                        .sons
+                        .iter()
+                        .chain(person.daughters.iter())
+                        .cloned()
+                        .collect(),
+                };
+                for relative in relatives {
+                    if self.visited.insert(relative) {
+                        self.queue.push_back((relative, generation + 1));
+                    }
+                }
+            }
+            // the person itself is the first generation, and people not matching the filter
+            // still lead to the next generations
+            if generation > 0 && self.filter.matches(person) {
+                return Some((person, generation));
+            }
+        }
+        None
+    }
+}
+
+impl Lineage {
+    /// Parents, grandparents and so on up to `max_depth` generations (all of them when None),
+    /// fathers before mothers in each generation
+    pub fn ancestors(&self, person: &Person, max_depth: Option<usize>) -> Generations<'_> {
+        Generations::new(
+            self,
+            person,
+            Direction::Parents,
+            max_depth,
+            PersonFilter::default(),
+        )
+    }
+
+    /// Children, grandchildren and so on up to `max_depth` generations (all of them when None),
+    /// matching the filter. The sons of each person come before its daughters, in insertion
+    /// order.
+    pub fn descendants(
+        &self,
+        person: &Person,
+        max_depth: Option<usize>,
+        filter: PersonFilter,
+    ) -> Generations<'_> {
+        Generations::new(self, person, Direction::Children, max_depth, filter)
+    }
+
+    /// The person with its ancestors up to `up` generations, its descendants down to `down`
+    /// generations with their other parents and, if `siblings`, its brothers and sisters, in
+    /// insertion order
+    pub fn neighbourhood(
+        &self,
+        person: &Person,
+        up: usize,
+        down: usize,
+        siblings: bool,
+    ) -> Vec<&Person> {
+        let mut idxs: HashSet<usize> = HashSet::new();
+        idxs.insert(person.idx);
+        idxs.extend(
+            self.ancestors(person, Some(up))
+                .map(|(ancestor, _)| ancestor.idx),
+        );
+        for (descendant, _) in self.descendants(person, Some(down), PersonFilter::default()) {
+            idxs.insert(descendant.idx);
+            idxs.extend(descendant.father.into_iter().chain(descendant.mother));
+        }
+        if siblings {
+            idxs.extend(self.get_brothers(person).iter().map(|brother| brother.idx));
+            idxs.extend(self.get_sisters(person).iter().map(|sister| sister.idx));
+        }
+        let mut people: Vec<&Person> = idxs
+            .into_iter()
+            .map(|idx| &self.people_graph[idx])
+            .collect();
+        people.sort_by_key(|person| person.idx);
+        people
+    }
+}
@@ -1,28 +1,147 @@
+mod generations;
+mod names;
 mod person;
+mod relationship;
+mod render;
+mod succession;
+pub use generations::{Generations, PersonFilter};
+use names::NameIndex;
+pub use names::{normalize, AmbiguousName, ResolveError, UnknownName};
 pub use person::{Person, Sex};
-use petgraph::dot::{Config, Dot};
-use petgraph::graph::NodeIndex;
-use petgraph::Graph;
-use serde::Deserialize;
-use std::collections::{HashMap, HashSet};
+pub use relationship::Relationship;
+pub use render::GraphOptions;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+pub use succession::{SuccessionLaw, SuccessionTier};
 
 #[cfg(test)]
 mod tests;
 
-#[derive(Debug, Clone)]
+#[cfg(test)]
+thread_local! {
+    /// Successions computed by the thread, for the tests checking when they are not needed
+    pub(crate) static SUCCESSIONS_COMPUTED: std::cell::Cell<usize> =
+        const { std::cell::Cell::new(0) };
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum KillError {
     PersonNotFound,
     PersonAlreadyDead,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReviveError {
+    PersonNotFound,
+    PersonAlive,
+}
+
+/// Outcome of each name of a batch kill, in the order they were given
+pub type KillOutcomes = Vec<Result<(), KillError>>;
+
+/// The living people whose heir changed because of a mutation, ordered by name
+pub type SuccessionChanges = Vec<SuccessionChange>;
+
+/// Reasons for refusing to add a person or a relationship, nothing is changed when one is returned
+#[derive(Debug, Clone, PartialEq)]
+pub enum InsertError {
+    /// Names must have at least a first name and a house name
+    MissingHouseName(String),
+    PersonAlreadyExists(String),
+    /// The person already exists with the other sex
+    SexMismatch(String),
+    /// The child is the parent itself or one of its ancestors
+    Cycle {
+        parent: String,
+        child: String,
+    },
+    RelationshipAlreadyExists {
+        parent: String,
+        child: String,
+    },
+    /// Several people have the name, the id must be given to pick one of them
+    AmbiguousName {
+        name: String,
+        candidates: Vec<String>,
+    },
+    /// The id is already the one of a person with another name
+    IdConflict {
+        id: String,
+        name: String,
+        existing_name: String,
+    },
+    /// The child already has a father (or mother) which is not the given parent
+    ParentAlreadySet {
+        child: String,
+        existing_parent: String,
+        new_parent: String,
+    },
+}
+
+impl fmt::Display for InsertError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InsertError::MissingHouseName(name) => {
+                write!(f, "Person must have at least first and house name {}", name)
+            }
+            InsertError::PersonAlreadyExists(name) => write!(f, "{} already exists", name),
+            InsertError::SexMismatch(name) => {
+                write!(f, "{} already exists with the other sex", name)
+            }
+            InsertError::Cycle { parent, child } => write!(
+                f,
+                "{} can not be a parent of {}, it is one of its descendants",
+                parent, child
+            ),
+            InsertError::RelationshipAlreadyExists { parent, child } => {
+                write!(f, "{} is already a parent of {}", parent, child)
+            }
+            InsertError::AmbiguousName { name, candidates } => write!(
+                f,
+                "{} is the name of several people, ids {}",
+                name,
+                candidates.join(", ")
+            ),
+            InsertError::IdConflict {
+                id,
+                name,
+                existing_name,
+            } => write!(f, "{} is the id of {}, not of {}", id, existing_name, name),
+            InsertError::ParentAlreadySet {
+                child,
+                existing_parent,
+                new_parent,
+            } => write!(
+                f,
+                "tried to overwrite parent {} with parent {} for child {}",
+                existing_parent, new_parent, child
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnlinkError {
+    PersonNotFound(String),
+    NotParentOf { parent: String, child: String },
+}
+
 /// Struct containing a parent child family relationship. It is consumed by Lineage in order to
-/// construct its family graph
-#[derive(Debug, Deserialize)]
+/// construct its family graph.
+/// The ids are optional, without them people are matched by name, and new people get an id
+/// derived from their name. They are needed to tell apart people with the same name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ParentChildInfo {
     pub parent_name: String,
     pub parent_sex: Sex,
     pub child_name: String,
     pub child_sex: Sex,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub child_id: Option<String>,
 }
 
 impl ParentChildInfo {
@@ -37,86 +156,392 @@ impl ParentChildInfo {
             parent_sex,
             child_name: child_name.into(),
             child_sex,
+            parent_id: None,
+            child_id: None,
         }
     }
+
+    pub fn with_ids<S: Into<String>>(mut self, parent_id: S, child_id: S) -> Self {
+        self.parent_id = Some(parent_id.into());
+        self.child_id = Some(child_id.into());
+        self
+    }
+}
+
+/// How two people having a parent in common are related
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SiblingKind {
+    /// Same father and same mother, both known
+    Full,
+    /// Same father only, the mothers being different or unknown
+    Paternal,
+    /// Same mother only, the fathers being different or unknown
+    Maternal,
+}
+
+/// The heir of a person and the succession tier it was found in
+#[derive(Debug, Clone, PartialEq)]
+pub struct Succession<'a> {
+    pub successor: &'a Person,
+    pub tier: SuccessionTier,
+}
+
+/// The heir of a living person changed after the lineage was modified
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct SuccessionChange {
+    pub person: String,
+    pub old_successor: Option<String>,
+    pub new_successor: Option<String>,
 }
 
 /// Represents a family lineage, contains the information of who is alive and the relationship
 /// between its members.
-/// `people_graph` contains the information itself and `people_graph_indexes` provides a way
-/// to translate a person's name to its index in `people_graph`
+/// `people_graph` contains the information itself and `people_ids` provides a way to translate a
+/// person's id to its index in `people_graph`. `names_indexes` lists the people bearing each name,
+/// usually a single one. `houses_indexes` lists the members of each house, so the house tier does
+/// not go through everybody.
 #[derive(Debug, Default)]
 pub struct Lineage {
     people_graph: Vec<Person>,
-    people_graph_indexes: HashMap<String, usize>,
+    people_ids: HashMap<String, usize>,
+    names_indexes: HashMap<String, Vec<usize>>,
+    houses_indexes: HashMap<String, Vec<usize>>,
+    /// Normalized names and aliases, to resolve the names typed by users
+    names: NameIndex,
+    /// Law of `next_in_line` and of the succession changes returned by the mutations
+    succession_law: SuccessionLaw,
+}
+
+/// Heirs of the people a mutation can affect, taken before applying it
+struct HeirsBefore {
+    heirs: HashMap<usize, Option<usize>>,
+    /// people from this index on are added by the mutation
+    people_count: usize,
 }
 
 impl Lineage {
-    pub fn next_in_line(&self, name: &str) -> Option<Person> {
-        let sort_and_get_first_alive = |mut input: Vec<&Person>| -> Option<Person> {
-            input.sort();
-            input
-                .iter()
-                .find(|person| person.alive)
-                .map(|person| (*person).to_owned())
-        };
-        let queried_person = self.get_from_name(name)?;
-        if let Some(son) = sort_and_get_first_alive(self.get_sons_of(queried_person)) {
-            return Some(son);
+    /// Returns the heir of the person with the id, or the name, `key` under the lineage law
+    pub fn next_in_line(&self, key: &str) -> Option<Person> {
+        self.next_in_line_under(key, self.succession_law)
+    }
+
+    /// Returns the heir of the person with the id, or the name, `key` under the given succession
+    /// law
+    pub fn next_in_line_under(&self, key: &str, law: SuccessionLaw) -> Option<Person> {
+        let queried_person = &self.people_graph[self.find(key)?];
+        self.succession_of(queried_person, law)
+            .map(|succession| succession.successor.to_owned())
+    }
+
+    /// Goes through each of the law tiers until finding someone alive, returning who it is and
+    /// from which tier it came
+    pub fn succession_of(&self, person: &Person, law: SuccessionLaw) -> Option<Succession<'_>> {
+        self.succession_skipping(person, law, &HashSet::new())
+    }
+
+    /// The heirs of the person in order, up to `max` of them (all of them when None): its heir,
+    /// the one who would inherit if the heir died, and so on
+    pub fn line_of_succession(
+        &self,
+        person: &Person,
+        law: SuccessionLaw,
+        max: Option<usize>,
+    ) -> Vec<Succession<'_>> {
+        let mut skipped = HashSet::new();
+        let mut line = vec![];
+        while max.is_none_or(|max| line.len() < max) {
+            match self.succession_skipping(person, law, &skipped) {
+                Some(succession) => {
+                    skipped.insert(succession.successor.idx);
+                    line.push(succession);
+                }
+                None => break,
+            }
+        }
+        line
+    }
+
+    /// `succession_of` as if the `skipped` people were dead
+    fn succession_skipping(
+        &self,
+        person: &Person,
+        law: SuccessionLaw,
+        skipped: &HashSet<usize>,
+    ) -> Option<Succession<'_>> {
+        #[cfg(test)]
+        SUCCESSIONS_COMPUTED.with(|count| count.set(count.get() + 1));
+        law.tiers().iter().find_map(|tier| {
+            self.get_ranked_tier_members(person, *tier, law)
+                .into_iter()
+                .find(|candidate| candidate.alive && !skipped.contains(&candidate.idx))
+                .map(|successor| Succession {
+                    successor,
+                    tier: *tier,
+                })
+        })
+    }
+
+    /// Siblings in the order they inherit from the person under `law`, followed by the ones the
+    /// law does not make heirs, brothers first and alphabetically
+    pub fn get_siblings_ranked(&self, person: &Person, law: SuccessionLaw) -> Vec<&Person> {
+        let mut seen = HashSet::new();
+        let mut ranked = vec![];
+        let sibling_tiers = law
+            .tiers()
+            .iter()
+            .filter(|tier| tier.is_siblings_tier())
+            .chain(&[SuccessionTier::Brothers, SuccessionTier::Sisters]);
+        for tier in sibling_tiers {
+            let mut siblings = self.get_tier_members(person, *tier);
+            siblings.sort();
+            ranked.extend(
+                siblings
+                    .into_iter()
+                    .filter(|sibling| seen.insert(sibling.idx)),
+            );
         }
-        if let Some(brother) = sort_and_get_first_alive(self.get_brothers(queried_person)) {
-            return Some(brother);
+        ranked
+    }
+
+    /// Children of the siblings, only the ones of `sex` if given, ranked by the seniority of the
+    /// sibling they descend from (see `get_siblings_ranked`), then alphabetically. A child of two
+    /// siblings is only listed once, with the most senior of them.
+    pub fn get_siblings_children_ranked(
+        &self,
+        person: &Person,
+        law: SuccessionLaw,
+        sex: Option<Sex>,
+    ) -> Vec<&Person> {
+        // a half-sibling can also be a parent
+        let mut seen: HashSet<usize> = [person.idx].iter().cloned().collect();
+        let mut ranked = vec![];
+        for sibling in self.get_siblings_ranked(person, law) {
+            let mut children: Vec<&Person> = self
+                .get_children_of(sibling)
+                .into_iter()
+                .filter(|child| sex.as_ref().is_none_or(|sex| child.sex == *sex))
+                .collect();
+            children.sort();
+            ranked.extend(children.into_iter().filter(|child| seen.insert(child.idx)));
         }
-        if let Some(nephew) = sort_and_get_first_alive(self.get_nephews(queried_person)) {
-            return Some(nephew);
+        ranked
+    }
+
+    /// Members of the tier in the order they inherit: alphabetically, except for the children of
+    /// the siblings who are first ranked by the seniority of their parent
+    fn get_ranked_tier_members(
+        &self,
+        person: &Person,
+        tier: SuccessionTier,
+        law: SuccessionLaw,
+    ) -> Vec<&Person> {
+        let sex = match tier {
+            SuccessionTier::Nephews => Some(Sex::Male),
+            SuccessionTier::Nieces => Some(Sex::Female),
+            SuccessionTier::SiblingsChildren => None,
+            _ => {
+                let mut members = self.get_tier_members(person, tier);
+                members.sort();
+                return members;
+            }
+        };
+        self.get_siblings_children_ranked(person, law, sex)
+    }
+
+    fn get_tier_members(&self, person: &Person, tier: SuccessionTier) -> Vec<&Person> {
+        match tier {
+            SuccessionTier::Sons => self.get_sons_of(person),
+            SuccessionTier::Brothers => self.get_brothers(person),
+            SuccessionTier::Nephews => self.get_nephews(person),
+            SuccessionTier::Daughters => self.get_daughters_of(person),
+            SuccessionTier::Sisters => self.get_sisters(person),
+            SuccessionTier::Nieces => self.get_nieces(person),
+            SuccessionTier::Children => {
+                let mut children = self.get_sons_of(person);
+                children.extend(self.get_daughters_of(person));
+                children
+            }
+            SuccessionTier::FullBrothers => {
+                self.get_siblings_of_kind(person, SiblingKind::Full, Sex::Male)
+            }
+            SuccessionTier::PaternalHalfBrothers => {
+                self.get_siblings_of_kind(person, SiblingKind::Paternal, Sex::Male)
+            }
+            SuccessionTier::MaternalHalfBrothers => {
+                self.get_siblings_of_kind(person, SiblingKind::Maternal, Sex::Male)
+            }
+            SuccessionTier::FullSisters => {
+                self.get_siblings_of_kind(person, SiblingKind::Full, Sex::Female)
+            }
+            SuccessionTier::PaternalHalfSisters => {
+                self.get_siblings_of_kind(person, SiblingKind::Paternal, Sex::Female)
+            }
+            SuccessionTier::MaternalHalfSisters => {
+                self.get_siblings_of_kind(person, SiblingKind::Maternal, Sex::Female)
+            }
+            SuccessionTier::Siblings => {
+                let mut siblings = self.get_brothers(person);
+                siblings.extend(self.get_sisters(person));
+                siblings
+            }
+            SuccessionTier::SiblingsChildren => {
+                let mut siblings_children = self.get_nephews(person);
+                siblings_children.extend(self.get_nieces(person));
+                siblings_children
+            }
+            SuccessionTier::House => self
+                .get_house_members(&person.house)
+                .into_iter()
+                .filter(|member| member.idx != person.idx)
+                .collect(),
         }
-        if let Some(daughter) = sort_and_get_first_alive(self.get_daughters_of(queried_person)) {
-            return Some(daughter);
+    }
+
+    fn get_siblings_of_kind(&self, person: &Person, kind: SiblingKind, sex: Sex) -> Vec<&Person> {
+        self.get_siblings_with_kind(person)
+            .into_iter()
+            .filter(|(sibling, sibling_kind)| *sibling_kind == kind && sibling.sex == sex)
+            .map(|(sibling, _)| sibling)
+            .collect()
+    }
+}
+
+// Succession changes caused by mutations. A person can only be the heir of its parents, its
+// siblings, the siblings of its parents and, through the house tier, of the members of its house.
+impl Lineage {
+    pub fn succession_law(&self) -> SuccessionLaw {
+        self.succession_law
+    }
+
+    /// Changes the law used by `next_in_line` and the mutations, meant to be called once after
+    /// loading the lineage: the heirs it changes are not reported
+    pub fn set_succession_law(&mut self, law: SuccessionLaw) {
+        self.succession_law = law;
+    }
+
+    /// People who could have `person` as their heir
+    fn heir_dependents(&self, person: &Person) -> HashSet<usize> {
+        let mut dependents = HashSet::new();
+        for parent in self
+            .get_father_of(person)
+            .into_iter()
+            .chain(self.get_mother_of(person))
+        {
+            dependents.insert(parent.idx);
+            dependents.extend(self.get_siblings_idx(parent));
         }
-        if let Some(sister) = sort_and_get_first_alive(self.get_sisters(queried_person)) {
-            return Some(sister);
+        dependents.extend(self.get_siblings_idx(person));
+        dependents.extend(self.house_dependents(person));
+        dependents
+    }
+
+    /// Living members of the house of the person whose heir in the house tier is, or would be,
+    /// the person: everybody if it comes first among the living in the order of the tier, the
+    /// first one if it comes second, nobody otherwise. The person does not need to be part of
+    /// the lineage yet, see `person_to_add`.
+    fn house_dependents(&self, person: &Person) -> Vec<usize> {
+        let mut living: Vec<&Person> = self
+            .get_house_members(&person.house)
+            .into_iter()
+            .filter(|member| member.alive && member.idx != person.idx)
+            .collect();
+        living.sort();
+        // members are sorted as in the tier, namesakes staying in the order they were added
+        let ranked_before = |member: &&&Person| {
+            (**member)
+                .cmp(person)
+                .then(member.idx.cmp(&person.idx))
+                .is_lt()
+        };
+        match living.iter().take_while(ranked_before).count() {
+            0 => living.iter().map(|member| member.idx).collect(),
+            1 => vec![living[0].idx],
+            _ => vec![],
         }
-        if let Some(niece) = sort_and_get_first_alive(self.get_nieces(queried_person)) {
-            return Some(niece);
+    }
+
+    /// The person which would be added at `idx`, to find its dependents before adding it
+    fn person_to_add(name: &str, sex: Sex, idx: usize) -> Person {
+        Person::new("", name, sex, idx)
+    }
+
+    fn get_siblings_idx<'a>(&'a self, person: &Person) -> impl Iterator<Item = usize> + 'a {
+        let mut siblings = self.get_brothers(person);
+        siblings.extend(self.get_sisters(person));
+        siblings.into_iter().map(|sibling| sibling.idx)
+    }
+
+    fn heirs_before(&self, people: impl IntoIterator<Item = usize>) -> HeirsBefore {
+        let heirs = people
+            .into_iter()
+            .map(|idx| (idx, self.heir_of(idx)))
+            .collect();
+        HeirsBefore {
+            heirs,
+            people_count: self.people_graph.len(),
         }
+    }
 
-        let mut alive_people_from_house: Vec<&Person> = self
-            .people()
-            .iter()
-            .filter(|each_person| {
-                (each_person.house == queried_person.house)
-                    && each_person.alive()
-                    && (each_person.name != queried_person.name)
+    fn heir_of(&self, idx: usize) -> Option<usize> {
+        self.succession_of(&self.people_graph[idx], self.succession_law)
+            .map(|succession| succession.successor.idx)
+    }
+
+    /// Compares the heirs taken before a mutation with the current ones, people added since had
+    /// no heir before
+    fn succession_changes_since(&self, before: HeirsBefore) -> SuccessionChanges {
+        let added = (before.people_count..self.people_graph.len()).map(|idx| (idx, None));
+        let name_of = |idx: Option<usize>| idx.map(|idx| self.people_graph[idx].name.clone());
+        let mut changes: SuccessionChanges = before
+            .heirs
+            .into_iter()
+            .chain(added)
+            .filter(|(idx, _)| self.people_graph[*idx].alive)
+            .filter_map(|(idx, old)| {
+                let new = self.heir_of(idx);
+                if old == new {
+                    return None;
+                }
+                Some(SuccessionChange {
+                    person: self.people_graph[idx].name.clone(),
+                    old_successor: name_of(old),
+                    new_successor: name_of(new),
+                })
             })
             .collect();
-        alive_people_from_house.sort();
-
-        alive_people_from_house.first().cloned().cloned()
+        changes.sort_by(|a, b| a.person.cmp(&b.person));
+        changes
     }
 }
 
-// Family accessors
+// Family accessors, none of them guarantees any ordering
 impl Lineage {
+    /// Sons of the person, in insertion order
     pub fn get_sons_of(&self, person: &Person) -> Vec<&Person> {
-        self.idx_to_person_vec(&person.sons_idx())
+        self.idx_to_person_vec(person.sons_idx())
     }
 
+    /// Daughters of the person, in insertion order
     pub fn get_daughters_of(&self, person: &Person) -> Vec<&Person> {
-        self.idx_to_person_vec(&person.daughters_idx())
+        self.idx_to_person_vec(person.daughters_idx())
     }
 
+    /// Mother of the person, if known
     pub fn get_mother_of(&self, person: &Person) -> Option<&Person> {
         let mother_idx = person.mother_idx()?;
         // if a person has a mother idx set and the idx is invalid it is a bug
         Some(self.get_from_idx(mother_idx).expect("Invalid mother idx"))
     }
 
+    /// Father of the person, if known
     pub fn get_father_of(&self, person: &Person) -> Option<&Person> {
         let father_idx = person.father_idx()?;
         // if a person has a father idx set and the idx is invalid it is a bug
         Some(self.get_from_idx(father_idx).expect("Invalid father idx"))
     }
 
+    /// Sons of the father or of the mother of the person, excluding the person itself
     pub fn get_brothers(&self, person: &Person) -> Vec<&Person> {
         let mut brothers: HashSet<&Person> = HashSet::new();
         if let Some(father) = self.get_father_of(person) {
@@ -133,6 +558,15 @@ impl Lineage {
         brothers.into_iter().collect()
     }
 
+    /// Members of the house, dead or alive, in insertion order
+    pub fn get_house_members(&self, house: &str) -> Vec<&Person> {
+        self.houses_indexes
+            .get(house)
+            .map(|idx_vec| self.idx_to_person_vec(idx_vec))
+            .unwrap_or_default()
+    }
+
+    /// Daughters of the father or of the mother of the person, excluding the person itself
     pub fn get_sisters(&self, person: &Person) -> Vec<&Person> {
         let mut sisters: HashSet<&Person> = HashSet::new();
         if let Some(father) = self.get_father_of(person) {
@@ -149,52 +583,202 @@ impl Lineage {
         sisters.into_iter().collect()
     }
 
-    /// Nephews are sons of the brothers or sisters
+    /// Nephews are sons of the brothers or sisters, a son of two of them is only listed once
     pub fn get_nephews(&self, person: &Person) -> Vec<&Person> {
-        let mut brothers_and_sisters = self.get_brothers(person);
-        brothers_and_sisters.extend_from_slice(&self.get_sisters(person));
-        let mut nephews = vec![];
-        for brother_or_sister in brothers_and_sisters {
-            nephews.extend_from_slice(&self.get_sons_of(brother_or_sister));
+        let mut nephews: HashSet<&Person> = HashSet::new();
+        for brother_or_sister in self
+            .get_brothers(person)
+            .into_iter()
+            .chain(self.get_sisters(person))
+        {
+            nephews.extend(self.get_sons_of(brother_or_sister));
         }
-        nephews
+        // a half-sibling can also be a parent
+        nephews.remove(person);
+        nephews.into_iter().collect()
     }
 
-    /// Nieces are daughters of the brothers or sisters
+    /// Nieces are daughters of the brothers or sisters, a daughter of two of them is only listed
+    /// once
     pub fn get_nieces(&self, person: &Person) -> Vec<&Person> {
-        let mut brothers_and_sisters = self.get_brothers(person);
-        brothers_and_sisters.extend_from_slice(&self.get_sisters(person));
-        let mut nieces = vec![];
-        for brother_or_sister in brothers_and_sisters {
-            nieces.extend_from_slice(&self.get_daughters_of(brother_or_sister));
+        let mut nieces: HashSet<&Person> = HashSet::new();
+        for brother_or_sister in self
+            .get_brothers(person)
+            .into_iter()
+            .chain(self.get_sisters(person))
+        {
+            nieces.extend(self.get_daughters_of(brother_or_sister));
+        }
+        // a half-sibling can also be a parent
+        nieces.remove(person);
+        nieces.into_iter().collect()
+    }
+
+    /// Father and mother of the person, if known
+    pub fn get_parents_of(&self, person: &Person) -> Vec<&Person> {
+        self.get_father_of(person)
+            .into_iter()
+            .chain(self.get_mother_of(person))
+            .collect()
+    }
+
+    /// Sons and daughters of the person, sons first
+    pub fn get_children_of(&self, person: &Person) -> Vec<&Person> {
+        let mut children = self.get_sons_of(person);
+        children.extend(self.get_daughters_of(person));
+        children
+    }
+
+    /// Parents of the father and of the mother
+    pub fn get_grandparents(&self, person: &Person) -> Vec<&Person> {
+        let mut grandparents: HashSet<&Person> = HashSet::new();
+        for parent in self.get_parents_of(person) {
+            grandparents.extend(self.get_parents_of(parent));
         }
-        nieces
+        grandparents.into_iter().collect()
     }
 
-    pub fn to_graphviz(&self) -> String {
-        let people = self.people();
-        let mut deps = Graph::<String, &str>::new();
-        let mut person_index_map: HashMap<usize, NodeIndex> = HashMap::new();
-        for (person_idx, person) in people.iter().enumerate() {
-            let idx = deps.add_node(format!("{} ({:?})", person.name(), person.sex()));
-            person_index_map.insert(person_idx, idx);
+    /// Children of the sons and of the daughters
+    pub fn get_grandchildren(&self, person: &Person) -> Vec<&Person> {
+        let mut grandchildren: HashSet<&Person> = HashSet::new();
+        for child in self.get_children_of(person) {
+            grandchildren.extend(self.get_children_of(child));
         }
+        grandchildren.into_iter().collect()
+    }
 
-        for (person_idx, person) in people.iter().enumerate() {
-            let person_node = person_index_map.get(&person_idx).unwrap();
-            for child in person.sons_idx() {
-                let son_node = person_index_map.get(&child).unwrap();
-                deps.add_edge(person_node.clone(), son_node.clone(), Default::default());
-            }
+    /// Brothers of the father and of the mother
+    pub fn get_uncles(&self, person: &Person) -> Vec<&Person> {
+        let mut uncles: HashSet<&Person> = HashSet::new();
+        for parent in self.get_parents_of(person) {
+            uncles.extend(self.get_brothers(parent));
+        }
+        uncles.into_iter().collect()
+    }
+
+    /// Sisters of the father and of the mother
+    pub fn get_aunts(&self, person: &Person) -> Vec<&Person> {
+        let mut aunts: HashSet<&Person> = HashSet::new();
+        for parent in self.get_parents_of(person) {
+            aunts.extend(self.get_sisters(parent));
+        }
+        aunts.into_iter().collect()
+    }
 
-            for child in person.daughters_idx() {
-                let son_node = person_index_map.get(&child).unwrap();
-                deps.add_edge(person_node.clone(), son_node.clone(), Default::default());
+    /// How `sibling` is related to `person`, None if they share no known parent or are the same
+    /// person
+    pub fn sibling_kind(&self, person: &Person, sibling: &Person) -> Option<SiblingKind> {
+        if person.idx == sibling.idx {
+            return None;
+        }
+        let shared = |a: Option<usize>, b: Option<usize>| a.is_some() && a == b;
+        match (
+            shared(person.father, sibling.father),
+            shared(person.mother, sibling.mother),
+        ) {
+            (true, true) => Some(SiblingKind::Full),
+            (true, false) => Some(SiblingKind::Paternal),
+            (false, true) => Some(SiblingKind::Maternal),
+            (false, false) => None,
+        }
+    }
+
+    /// Brothers and sisters of the person with how they are related to it, brothers first
+    pub fn get_siblings_with_kind(&self, person: &Person) -> Vec<(&Person, SiblingKind)> {
+        let mut siblings = self.get_brothers(person);
+        siblings.extend(self.get_sisters(person));
+        siblings
+            .into_iter()
+            .filter_map(|sibling| {
+                self.sibling_kind(person, sibling)
+                    .map(|kind| (sibling, kind))
+            })
+            .collect()
+    }
+
+    /// Brothers and sisters having both the father and the mother of the person, both known
+    pub fn get_full_siblings(&self, person: &Person) -> Vec<&Person> {
+        self.get_siblings_with_kind(person)
+            .into_iter()
+            .filter(|(_, kind)| *kind == SiblingKind::Full)
+            .map(|(sibling, _)| sibling)
+            .collect()
+    }
+
+    /// Brothers and sisters sharing only one parent with the person
+    pub fn get_half_siblings(&self, person: &Person) -> Vec<&Person> {
+        self.get_siblings_with_kind(person)
+            .into_iter()
+            .filter(|(_, kind)| *kind != SiblingKind::Full)
+            .map(|(sibling, _)| sibling)
+            .collect()
+    }
+
+    /// Cousins of the given degree, 1 for first cousins sharing grandparents, 2 for second
+    /// cousins sharing great-grandparents... Closer relatives are excluded, so a half-sibling
+    /// is never a cousin. Degree 0 gives the siblings.
+    pub fn get_cousins(&self, person: &Person, degree: usize) -> Vec<&Person> {
+        // descendants of the ancestors `generations` up, as many generations down
+        let same_generation = |generations: usize| -> HashSet<&Person> {
+            let mut relatives = HashSet::new();
+            if generations == 0 {
+                relatives.insert(&self.people_graph[person.idx]);
+            }
+            for (ancestor, _) in self
+                .ancestors(person, Some(generations))
+                .filter(|(_, generation)| *generation == generations)
+            {
+                relatives.extend(
+                    self.descendants(ancestor, Some(generations), PersonFilter::default())
+                        .filter(|(_, generation)| *generation == generations)
+                        .map(|(relative, _)| relative),
+                );
+            }
+            relatives
+        };
+        let mut cousins = same_generation(degree + 1);
+        for closer in 0..=degree {
+            for relative in same_generation(closer) {
+                cousins.remove(relative);
             }
         }
+        cousins.into_iter().collect()
+    }
 
-        let graphviz = Dot::with_config(&deps, &[Config::EdgeNoLabel]);
-        graphviz.to_string()
+    /// Shortest chain of parent/child links going from `from` to `to`, both included. Returns
+    /// None if they are not related by blood.
+    pub fn kinship_path(&self, from: &Person, to: &Person) -> Option<Vec<&Person>> {
+        // breadth first search storing from which person each one was reached
+        let mut reached_from: HashMap<usize, usize> = HashMap::new();
+        let mut queue = VecDeque::new();
+        reached_from.insert(from.idx, from.idx);
+        queue.push_back(from.idx);
+        while let Some(current_idx) = queue.pop_front() {
+            if current_idx == to.idx {
+                let mut path = vec![&self.people_graph[current_idx]];
+                let mut idx = current_idx;
+                while idx != from.idx {
+                    idx = reached_from[&idx];
+                    path.push(&self.people_graph[idx]);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            let current = &self.people_graph[current_idx];
+            let relatives = current
+                .father
+                .iter()
+                .chain(current.mother.iter())
+                .chain(current.sons.iter())
+                .chain(current.daughters.iter());
+            for relative_idx in relatives {
+                if !reached_from.contains_key(relative_idx) {
+                    reached_from.insert(*relative_idx, current_idx);
+                    queue.push_back(*relative_idx);
+                }
+            }
+        }
+        None
     }
 }
 
@@ -207,22 +791,100 @@ impl Lineage {
         idx_vec.iter().map(|idx| &self.people_graph[*idx]).collect()
     }
 
-    fn insert_or_get_existing(&mut self, name: &str, sex: Sex) -> usize {
-        if self.people_graph_indexes.get(name).is_none() {
-            // person is not in Lineage yet, insert it in graph
-            let index = self.people_graph.len();
-            self.people_graph.push(Person::new(name, sex, index));
-            // update hashmap with index
-            self.people_graph_indexes.insert(name.to_string(), index);
-            index
-        } else {
-            *self.people_graph_indexes.get(name).unwrap()
+    /// The person `find_for_insert` finds, inserted in the graph if needed. The name and id must
+    /// have been validated.
+    fn insert_or_get_existing(&mut self, name: &str, id: Option<&str>, sex: Sex) -> usize {
+        if let Some(idx) = self
+            .find_for_insert(name, id)
+            .expect("name and id must be validated")
+        {
+            return idx;
+        }
+        // person is not in Lineage yet, insert it in graph
+        let index = self.people_graph.len();
+        let id = match id {
+            Some(id) => id.to_string(),
+            None => self.new_id(name),
+        };
+        let person = Person::new(&id, name, sex, index);
+        self.houses_indexes
+            .entry(person.house.clone())
+            .or_default()
+            .push(index);
+        self.people_graph.push(person);
+        // update hashmaps with index
+        self.people_ids.insert(id, index);
+        self.names_indexes
+            .entry(name.to_string())
+            .or_default()
+            .push(index);
+        self.names.insert(name, index);
+        index
+    }
+
+    /// Id derived from the name, suffixed with a number if another person already has it
+    fn new_id(&self, name: &str) -> String {
+        let id = Person::id_from_name(name);
+        if !self.people_ids.contains_key(&id) {
+            return id;
+        }
+        (2..)
+            .map(|n| format!("{}-{}", id, n))
+            .find(|id| !self.people_ids.contains_key(id))
+            .expect("there are unused suffixes")
+    }
+
+    /// The person an inserted relationship or person refers to: the one with the id if given,
+    /// otherwise the only one with the name. None if it is not part of the lineage yet.
+    fn find_for_insert(&self, name: &str, id: Option<&str>) -> Result<Option<usize>, InsertError> {
+        if let Some(id) = id {
+            return match self.people_ids.get(id) {
+                Some(idx) if self.people_graph[*idx].name != name => Err(InsertError::IdConflict {
+                    id: id.to_string(),
+                    name: name.to_string(),
+                    existing_name: self.people_graph[*idx].name.clone(),
+                }),
+                idx => Ok(idx.cloned()),
+            };
+        }
+        match self.names_indexes.get(name).map(Vec::as_slice) {
+            None | Some([]) => Ok(None),
+            Some([idx]) => Ok(Some(*idx)),
+            Some(idxs) => Err(InsertError::AmbiguousName {
+                name: name.to_string(),
+                candidates: self.ids_of(idxs),
+            }),
         }
     }
 
+    fn ids_of(&self, idxs: &[usize]) -> Vec<String> {
+        idxs.iter()
+            .map(|idx| self.people_graph[*idx].id.clone())
+            .collect()
+    }
+
+    /// Index of the person with the id `key`, or else of the only person named `key`
+    fn find(&self, key: &str) -> Option<usize> {
+        if let Some(idx) = self.people_ids.get(key) {
+            return Some(*idx);
+        }
+        match self.names_indexes.get(key).map(Vec::as_slice) {
+            Some([idx]) => Some(*idx),
+            _ => None,
+        }
+    }
+
+    /// The person named exactly `name`, None if there are several
     pub fn get_from_name(&self, name: &str) -> Option<&Person> {
-        let idx = self.people_graph_indexes.get(name)?;
-        self.people_graph.get(idx.clone())
+        match self.names_indexes.get(name).map(Vec::as_slice) {
+            Some([idx]) => self.people_graph.get(*idx),
+            _ => None,
+        }
+    }
+
+    pub fn get_from_id(&self, id: &str) -> Option<&Person> {
+        let idx = self.people_ids.get(id)?;
+        self.people_graph.get(*idx)
     }
 
     pub fn get_from_idx(&self, idx: usize) -> Option<&Person> {
@@ -233,27 +895,193 @@ impl Lineage {
         &self.people_graph
     }
 
-    /// Returns true if the person was found and killed, false if the person did not exist
-    pub fn kill(&mut self, person_name: &str) -> Result<(), KillError> {
-        if let Some(person_idx) = self.people_graph_indexes.get(person_name).cloned() {
+    /// The names borne by the people of the lineage, sorted, each once
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.names_indexes.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Every parent/child link of the lineage, children in insertion order and fathers before
+    /// mothers. Ids are only given when they are not the ones derived from the names, so loading
+    /// the relationships back gives the same lineage, apart from who is dead and from the people
+    /// without any relative.
+    pub fn relationships(&self) -> Vec<ParentChildInfo> {
+        let mut relationships = vec![];
+        for child in &self.people_graph {
+            for parent in child.father.into_iter().chain(child.mother) {
+                let parent = &self.people_graph[parent];
+                let mut relationship = ParentChildInfo::new(
+                    parent.name.clone(),
+                    parent.sex.clone(),
+                    child.name.clone(),
+                    child.sex.clone(),
+                );
+                if parent.id != Person::id_from_name(&parent.name)
+                    || child.id != Person::id_from_name(&child.name)
+                {
+                    relationship = relationship.with_ids(parent.id.clone(), child.id.clone());
+                }
+                relationships.push(relationship);
+            }
+        }
+        relationships
+    }
+
+    /// Kills the person with the id, or the name, `key`, returning whose heir changed because of
+    /// it. A name borne by several people is not found.
+    pub fn kill(&mut self, key: &str) -> Result<SuccessionChanges, KillError> {
+        if let Some(person_idx) = self.find(key) {
             if !self.people_graph[person_idx].alive {
                 return Err(KillError::PersonAlreadyDead);
             }
+            let before = self.heirs_before(self.heir_dependents(&self.people_graph[person_idx]));
             self.people_graph[person_idx].kill();
-            Ok(())
+            Ok(self.succession_changes_since(before))
         } else {
             Err(KillError::PersonNotFound)
         }
     }
 
+    /// Brings a dead person back to life, returning whose heir changed because of it. Looks the
+    /// person up as `kill` does.
+    pub fn revive(&mut self, key: &str) -> Result<SuccessionChanges, ReviveError> {
+        let person_idx = match self.find(key) {
+            Some(idx) => idx,
+            None => return Err(ReviveError::PersonNotFound),
+        };
+        if self.people_graph[person_idx].alive {
+            return Err(ReviveError::PersonAlive);
+        }
+        // the people who could have it as heir are the same whether it is dead or alive
+        let before = self.heirs_before(self.heir_dependents(&self.people_graph[person_idx]));
+        self.people_graph[person_idx].revive();
+        Ok(self.succession_changes_since(before))
+    }
+
+    /// Kills everybody in `names` at once, but only if all of them are part of the lineage.
+    /// People are looked up by id or name as in `kill`.
+    /// Returns the outcome of each name, in order: people already dead (or repeated in `names`)
+    /// are reported as `PersonAlreadyDead` without preventing the others from being killed.
+    /// If any is `PersonNotFound` nobody is killed and the outcomes are returned as an error,
+    /// otherwise they come with whose heir changed.
+    pub fn kill_batch<S: AsRef<str>>(
+        &mut self,
+        names: &[S],
+    ) -> Result<(KillOutcomes, SuccessionChanges), KillOutcomes> {
+        let mut to_kill = HashSet::new();
+        let outcomes: KillOutcomes = names
+            .iter()
+            .map(|name| match self.find(name.as_ref()) {
+                None => Err(KillError::PersonNotFound),
+                Some(idx) if !self.people_graph[idx].alive || !to_kill.insert(idx) => {
+                    Err(KillError::PersonAlreadyDead)
+                }
+                Some(_) => Ok(()),
+            })
+            .collect();
+        if outcomes
+            .iter()
+            .any(|outcome| matches!(outcome, Err(KillError::PersonNotFound)))
+        {
+            return Err(outcomes);
+        }
+        let dependents = to_kill
+            .iter()
+            .flat_map(|idx| self.heir_dependents(&self.people_graph[*idx]))
+            .collect::<Vec<usize>>();
+        let before = self.heirs_before(dependents);
+        for idx in to_kill {
+            self.people_graph[idx].kill();
+        }
+        Ok((outcomes, self.succession_changes_since(before)))
+    }
+
+    /// Same as `try_insert` without telling whose heir changed, for loading datasets: finding the
+    /// changes takes longer as the houses grow, it would make loading a big house very slow
+    pub fn load(&mut self, parent_child_info: ParentChildInfo) -> Result<(), InsertError> {
+        self.validate_insert(&parent_child_info)?;
+        self.link(parent_child_info);
+        Ok(())
+    }
+
+    /// Same as `load` but panics if the relationship is invalid, for loading datasets known to be
+    /// valid
     pub fn insert(&mut self, parent_child_info: ParentChildInfo) {
+        if let Err(e) = self.load(parent_child_info) {
+            panic!("{}", e);
+        }
+    }
+
+    /// Adds a parent child relationship, inserting the parent and child if they are not in the
+    /// lineage yet, and returns whose heir changed. The lineage is left untouched if the
+    /// relationship is invalid.
+    pub fn try_insert(
+        &mut self,
+        parent_child_info: ParentChildInfo,
+    ) -> Result<SuccessionChanges, InsertError> {
+        self.validate_insert(&parent_child_info)?;
+        // the child becomes a sibling of the other children of the parent, and a nephew of its
+        // siblings, new people join a house
+        let mut dependents = HashSet::new();
+        let parent_idx = self.find_for_insert(
+            &parent_child_info.parent_name,
+            parent_child_info.parent_id.as_deref(),
+        )?;
+        let child_idx = self.find_for_insert(
+            &parent_child_info.child_name,
+            parent_child_info.child_id.as_deref(),
+        )?;
+        // new people are added child first, after everybody else
+        let mut next_idx = self.people_graph.len();
+        for (name, sex, idx) in &[
+            (
+                &parent_child_info.child_name,
+                &parent_child_info.child_sex,
+                child_idx,
+            ),
+            (
+                &parent_child_info.parent_name,
+                &parent_child_info.parent_sex,
+                parent_idx,
+            ),
+        ] {
+            match idx {
+                Some(idx) => {
+                    dependents.insert(*idx);
+                }
+                None => {
+                    let person = Self::person_to_add(name, (*sex).clone(), next_idx);
+                    dependents.extend(self.house_dependents(&person));
+                    next_idx += 1;
+                }
+            }
+        }
+        if let Some(parent_idx) = parent_idx {
+            let parent = &self.people_graph[parent_idx];
+            dependents.extend(self.get_children_idx(parent));
+            dependents.extend(self.get_siblings_idx(parent));
+        }
+        let before = self.heirs_before(dependents);
+        self.link(parent_child_info);
+        Ok(self.succession_changes_since(before))
+    }
+
+    fn get_children_idx<'a>(&'a self, person: &'a Person) -> impl Iterator<Item = usize> + 'a {
+        person.sons.iter().chain(person.daughters.iter()).cloned()
+    }
+
+    /// Links the parent and the child, inserting them if needed, the relationship must be valid
+    fn link(&mut self, parent_child_info: ParentChildInfo) {
         // insert or get the existing index of the parent and child in the graph
         let child_idx = self.insert_or_get_existing(
             &parent_child_info.child_name,
+            parent_child_info.child_id.as_deref(),
             parent_child_info.child_sex.clone(),
         );
         let parent_idx = self.insert_or_get_existing(
             &parent_child_info.parent_name,
+            parent_child_info.parent_id.as_deref(),
             parent_child_info.parent_sex.clone(),
         );
         // update parent info about son or daughter
@@ -265,34 +1093,156 @@ impl Lineage {
                 self.people_graph[parent_idx].daughters.push(child_idx);
             }
         }
-        // update child info about father or mother, panic if the father or mother were already set
-        let child = &self.people_graph[child_idx];
-
+        // update child info about father or mother, already checked they were not set
         match &parent_child_info.parent_sex {
-            Sex::Male => {
-                assert!(
-                    child.father.is_none(),
-                    format!(
-                        "tried to overwrite father {} with father {} for child {}",
-                        self.get_father_of(child).unwrap().name,
-                        parent_child_info.parent_name,
-                        parent_child_info.child_name
-                    )
-                );
-                self.people_graph[child_idx].father = Some(parent_idx);
+            Sex::Male => self.people_graph[child_idx].father = Some(parent_idx),
+            Sex::Female => self.people_graph[child_idx].mother = Some(parent_idx),
+        }
+    }
+
+    fn validate_insert(&self, parent_child_info: &ParentChildInfo) -> Result<(), InsertError> {
+        let ParentChildInfo {
+            parent_name,
+            parent_sex,
+            child_name,
+            child_sex,
+            parent_id,
+            child_id,
+        } = parent_child_info;
+        for name in &[parent_name, child_name] {
+            if Person::house_from_name(name).is_none() {
+                return Err(InsertError::MissingHouseName(name.to_string()));
             }
-            Sex::Female => {
-                assert!(
-                    child.mother.is_none(),
-                    format!(
-                        "tried to overwrite mother {} with mother {} for child {}",
-                        self.get_mother_of(child).unwrap().name,
-                        parent_child_info.parent_name,
-                        parent_child_info.child_name
-                    )
-                );
-                self.people_graph[child_idx].mother = Some(parent_idx);
+        }
+        let parent = self.find_for_insert(parent_name, parent_id.as_deref())?;
+        let child = self.find_for_insert(child_name, child_id.as_deref())?;
+        for (name, sex, idx) in &[
+            (parent_name, parent_sex, parent),
+            (child_name, child_sex, child),
+        ] {
+            if let Some(idx) = idx {
+                if self.people_graph[*idx].sex != **sex {
+                    return Err(InsertError::SexMismatch(name.to_string()));
+                }
+            }
+        }
+        let child = match child {
+            Some(idx) => &self.people_graph[idx],
+            // the child is new, it has no parents yet
+            None => return Ok(()),
+        };
+        if let Some(parent) = parent.map(|idx| &self.people_graph[idx]) {
+            if parent.idx == child.idx || self.is_ancestor_of(child, parent) {
+                return Err(InsertError::Cycle {
+                    parent: parent_name.to_string(),
+                    child: child_name.to_string(),
+                });
             }
         }
+        let existing_parent = match parent_sex {
+            Sex::Male => self.get_father_of(child),
+            Sex::Female => self.get_mother_of(child),
+        };
+        match existing_parent {
+            None => Ok(()),
+            Some(existing_parent) if Some(existing_parent.idx) == parent => {
+                Err(InsertError::RelationshipAlreadyExists {
+                    parent: parent_name.to_string(),
+                    child: child_name.to_string(),
+                })
+            }
+            Some(existing_parent) => Err(InsertError::ParentAlreadySet {
+                child: child_name.to_string(),
+                existing_parent: existing_parent.name.to_string(),
+                new_parent: parent_name.to_string(),
+            }),
+        }
+    }
+
+    /// True if `ancestor` can be reached going up the parents of `person`
+    fn is_ancestor_of(&self, ancestor: &Person, person: &Person) -> bool {
+        let mut to_visit = vec![person];
+        let mut visited = HashSet::new();
+        while let Some(current) = to_visit.pop() {
+            if !visited.insert(current.idx) {
+                continue;
+            }
+            for parent in self
+                .get_father_of(current)
+                .into_iter()
+                .chain(self.get_mother_of(current))
+            {
+                if parent.idx == ancestor.idx {
+                    return true;
+                }
+                to_visit.push(parent);
+            }
+        }
+        false
+    }
+
+    /// Adds a person without any relatives, returning whose heir changed: only members of its
+    /// house can be affected. Adding someone with the name of a person already in the lineage
+    /// requires an id.
+    pub fn add_person(
+        &mut self,
+        name: &str,
+        sex: Sex,
+        id: Option<&str>,
+    ) -> Result<SuccessionChanges, InsertError> {
+        if Person::house_from_name(name).is_none() {
+            return Err(InsertError::MissingHouseName(name.to_string()));
+        }
+        let existing = match id {
+            Some(id) => self.people_ids.contains_key(id),
+            None => self.names_indexes.contains_key(name),
+        };
+        if existing {
+            return Err(InsertError::PersonAlreadyExists(
+                id.unwrap_or(name).to_string(),
+            ));
+        }
+        let person = Self::person_to_add(name, sex.clone(), self.people_graph.len());
+        let before = self.heirs_before(self.house_dependents(&person));
+        self.insert_or_get_existing(name, id, sex);
+        Ok(self.succession_changes_since(before))
+    }
+
+    /// Removes the parent child relationship between the two, both people stay in the lineage.
+    /// They are looked up by id or name as in `kill`. Returns whose heir changed.
+    pub fn remove_relationship(
+        &mut self,
+        parent_name: &str,
+        child_name: &str,
+    ) -> Result<SuccessionChanges, UnlinkError> {
+        let idx_of = |name: &str| {
+            self.find(name)
+                .ok_or_else(|| UnlinkError::PersonNotFound(name.to_string()))
+        };
+        let parent_idx = idx_of(parent_name)?;
+        let child_idx = idx_of(child_name)?;
+        // mirrors try_insert, the child stops being a sibling of the other children of the
+        // parent and a nephew of its siblings
+        let parent = &self.people_graph[parent_idx];
+        let mut dependents: HashSet<usize> = self.get_children_idx(parent).collect();
+        dependents.extend(self.get_siblings_idx(parent));
+        dependents.insert(parent_idx);
+        dependents.insert(child_idx);
+        let before = self.heirs_before(dependents);
+        let child = &mut self.people_graph[child_idx];
+        if child.father == Some(parent_idx) {
+            child.father = None;
+        } else if child.mother == Some(parent_idx) {
+            child.mother = None;
+        } else {
+            return Err(UnlinkError::NotParentOf {
+                parent: parent_name.to_string(),
+                child: child_name.to_string(),
+            });
+        }
+        let parent = &mut self.people_graph[parent_idx];
+        parent.sons.retain(|idx| *idx != child_idx);
+        parent.daughters.retain(|idx| *idx != child_idx);
+        Ok(self.succession_changes_since(before))
     }
 }
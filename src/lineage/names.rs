@@ -0,0 +1,165 @@
+//! Resolution of the names typed by users: the id, the exact name, then the name regardless of
+//! case and whitespace, then the aliases, and otherwise suggestions of close names
+use super::{Lineage, Person};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Suggestions returned for an unknown name
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Lower case with single spaces, names are compared in this form
+pub fn normalize(name: &str) -> String {
+    name.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The name matches nobody of the lineage, `suggestions` are the closest names, best first
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownName {
+    pub name: String,
+    pub suggestions: Vec<String>,
+}
+
+/// The name is borne by several people, `candidates` are their ids
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmbiguousName {
+    pub name: String,
+    pub candidates: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResolveError {
+    Unknown(UnknownName),
+    Ambiguous(AmbiguousName),
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ResolveError::Unknown(unknown) if unknown.suggestions.is_empty() => {
+                write!(f, "nobody is named {}", unknown.name)
+            }
+            ResolveError::Unknown(unknown) => write!(
+                f,
+                "nobody is named {}, did you mean {}?",
+                unknown.name,
+                unknown.suggestions.join(", ")
+            ),
+            ResolveError::Ambiguous(ambiguous) => write!(
+                f,
+                "several people are named {}, use one of the ids {}",
+                ambiguous.name,
+                ambiguous.candidates.join(", ")
+            ),
+        }
+    }
+}
+
+/// Normalized names and aliases, to the indexes of the people in the graph
+#[derive(Debug, Default)]
+pub(super) struct NameIndex {
+    normalized: HashMap<String, Vec<usize>>,
+    aliases: HashMap<String, usize>,
+}
+
+impl NameIndex {
+    pub(super) fn insert(&mut self, name: &str, idx: usize) {
+        self.normalized
+            .entry(normalize(name))
+            .or_default()
+            .push(idx);
+    }
+}
+
+impl Lineage {
+    /// Adds `alias` as another name of the person with the id, or the exact name, `key`. A full
+    /// alias ("Kingslayer") is resolved on its own, an alias of the first names ("Ned") is also
+    /// resolved followed by the house name ("Ned Stark").
+    pub fn add_alias(&mut self, alias: &str, key: &str) -> Result<(), ResolveError> {
+        let idx = match self.people_ids.get(key) {
+            Some(idx) => *idx,
+            None => {
+                let idxs = self.names_indexes.get(key).cloned().unwrap_or_default();
+                self.single(key, &idxs)?.idx
+            }
+        };
+        self.names.aliases.insert(normalize(alias), idx);
+        Ok(())
+    }
+
+    /// Looks up the person a user refers to as `key`, an id or a name
+    pub fn resolve(&self, key: &str) -> Result<&Person, ResolveError> {
+        if let Some(person) = self.get_from_id(key) {
+            return Ok(person);
+        }
+        if let Some(idxs) = self.names_indexes.get(key) {
+            return self.single(key, idxs);
+        }
+        let normalized = normalize(key);
+        if let Some(idxs) = self.names.normalized.get(&normalized) {
+            return self.single(key, idxs);
+        }
+        if let Some(idx) = self.names.aliases.get(&normalized) {
+            return Ok(&self.people_graph[*idx]);
+        }
+        // alias of the first names followed by the house name
+        if let Some((first_names, house)) = normalized.rsplit_once(' ') {
+            if let Some(idx) = self.names.aliases.get(first_names) {
+                let person = &self.people_graph[*idx];
+                if normalize(&person.house) == house {
+                    return Ok(person);
+                }
+            }
+        }
+        Err(self.unknown_name(key))
+    }
+
+    /// The person of `idxs`, all bearing `name`, if there is a single one
+    fn single(&self, name: &str, idxs: &[usize]) -> Result<&Person, ResolveError> {
+        match idxs {
+            [] => Err(self.unknown_name(name)),
+            [idx] => Ok(&self.people_graph[*idx]),
+            _ => Err(ResolveError::Ambiguous(AmbiguousName {
+                name: name.to_string(),
+                candidates: self.ids_of(idxs),
+            })),
+        }
+    }
+
+    /// The names closest to `name` by edit distance, aliases suggesting the name they stand for
+    fn unknown_name(&self, name: &str) -> ResolveError {
+        let normalized = normalize(name);
+        // farther names are unlikely to be typos
+        let max_distance = (normalized.chars().count() / 4).max(3);
+        let mut candidates: Vec<(usize, &str)> = self
+            .names
+            .normalized
+            .iter()
+            .flat_map(|(candidate, idxs)| idxs.iter().map(move |idx| (candidate, idx)))
+            .chain(self.names.aliases.iter())
+            .map(|(candidate, idx)| {
+                (
+                    strsim::levenshtein(&normalized, candidate),
+                    self.people_graph[*idx].name.as_str(),
+                )
+            })
+            .filter(|(distance, _)| *distance <= max_distance)
+            .collect();
+        candidates.sort();
+        let mut suggestions: Vec<String> = vec![];
+        for (_, candidate) in candidates {
+            if suggestions.len() == MAX_SUGGESTIONS {
+                break;
+            }
+            if !suggestions.iter().any(|suggestion| suggestion == candidate) {
+                suggestions.push(candidate.to_string());
+            }
+        }
+        ResolveError::Unknown(UnknownName {
+            name: name.to_string(),
+            suggestions,
+        })
+    }
+}
@@ -1,21 +1,25 @@
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
 pub enum Sex {
-    #[serde(rename(deserialize = "M"))]
+    #[serde(rename = "M")]
     Male,
-    #[serde(rename(deserialize = "F"))]
+    #[serde(rename = "F")]
     Female,
 }
 
 /// Represents a person of the family. Fields are private outside super to help avoid the creation of
-/// an invalid person (setting father to an invalid id for example)
+/// an invalid person (setting father to an invalid index for example)
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Person {
-    /// Its own id in the graph, useful for linking a standalone (copy) of a Person struct to the
-    /// original struct in the graph
-    pub(super) id: usize,
+    /// Its own index in the graph, useful for linking a standalone (copy) of a Person struct to
+    /// the original struct in the graph
+    pub(super) idx: usize,
+    /// Stable identifier, declared by the datasets or derived from the name. Unlike the name it is
+    /// unique.
+    pub(super) id: String,
     pub(super) name: String,
     pub(super) house: String,
     pub(super) sex: Sex,
@@ -36,23 +40,20 @@ impl Ord for Person {
 /// People are ordered alphabetically
 impl PartialOrd for Person {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.name.cmp(&other.name))
+        Some(self.cmp(other))
     }
 }
 
 impl Person {
-    pub(super) fn new(name: &str, sex: Sex, id: usize) -> Self {
-        let split_names: Vec<&str> = name.trim().split_ascii_whitespace().collect();
+    pub(super) fn new(id: &str, name: &str, sex: Sex, idx: usize) -> Self {
         // Person must have at least 2 names, first name and house name
-        assert!(
-            split_names.len() >= 2,
-            format!("Person must have at least first and house name {}", name)
-        );
-        let house = (*split_names.last().unwrap()).to_string();
+        let house = Self::house_from_name(name)
+            .unwrap_or_else(|| panic!("Person must have at least first and house name {}", name));
         Person {
-            id,
+            idx,
+            id: id.to_string(),
             name: name.to_string(),
-            house,
+            house: house.to_string(),
             sex,
             alive: true,
             father: None,
@@ -62,14 +63,42 @@ impl Person {
         }
     }
 
-    pub fn id(&self) -> usize {
-        self.id
+    /// Identifier of a person whose dataset does not declare one: the name in lower case with
+    /// dashes, e.g. eddard-stark
+    pub fn id_from_name(name: &str) -> String {
+        name.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    /// The house is the last of the names, returns None if there is not at least a first and a
+    /// house name
+    pub fn house_from_name(name: &str) -> Option<&str> {
+        let split_names: Vec<&str> = name.trim().split_ascii_whitespace().collect();
+        if split_names.len() < 2 {
+            return None;
+        }
+        split_names.last().cloned()
+    }
+
+    pub fn idx(&self) -> usize {
+        self.idx
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
     }
 
     pub fn name(&self) -> &str {
         &self.name
     }
 
+    pub fn house(&self) -> &str {
+        &self.house
+    }
+
     pub fn sex(&self) -> Sex {
         self.sex.clone()
     }
@@ -84,6 +113,11 @@ impl Person {
         self.alive = false;
     }
 
+    /// Same as `kill`, must go through the "Lineage" struct
+    pub(super) fn revive(&mut self) {
+        self.alive = true;
+    }
+
     pub fn sons_idx(&self) -> &Vec<usize> {
         &self.sons
     }
@@ -0,0 +1,165 @@
+//! Blood relationship between two people, named from the number of generations separating each
+//! of them from their lowest common ancestors
+use super::{Lineage, Person, Sex, SiblingKind};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+/// How a person is related to another one, see `Lineage::relationship`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Relationship<'a> {
+    /// e.g. "first cousin once removed", "great-uncle" or "half-sister"
+    pub name: String,
+    /// Generations from the first person up to the common ancestors
+    pub generations_up: usize,
+    /// Generations from the common ancestors down to the second person
+    pub generations_down: usize,
+    /// Only one of the parents the lines go through is shared, a missing parent not being shared
+    /// as for the succession, see `Lineage::sibling_kind`
+    pub half: bool,
+    /// The closest ancestors shared by both, usually a couple. The person itself when one is an
+    /// ancestor of the other.
+    pub common_ancestors: Vec<&'a Person>,
+    /// From the first person up to one of the common ancestors and down to the second person,
+    /// both included
+    pub path: Vec<&'a Person>,
+}
+
+/// Ancestors of a person, itself included, with their distance in generations and the child
+/// they were reached from
+type Ancestry = HashMap<usize, (usize, Option<usize>)>;
+
+impl Lineage {
+    /// How `b` is related to `a` by blood ("`b` is the first cousin of `a`"), None if they have
+    /// no common ancestor in the lineage
+    pub fn relationship<'a>(&'a self, a: &Person, b: &Person) -> Option<Relationship<'a>> {
+        let a_ancestry = self.ancestry(a.idx);
+        let b_ancestry = self.ancestry(b.idx);
+        // the closest common ancestors, ties broken by the smallest difference of generations,
+        // then by the fewest generations up and the first ancestor added, as the ancestries are
+        // visited in no particular order
+        let (up, down, _) = a_ancestry
+            .iter()
+            .filter_map(|(idx, (up, _))| b_ancestry.get(idx).map(|(down, _)| (*up, *down, *idx)))
+            .min_by_key(|(up, down, idx)| (up + down, up.max(down) - up.min(down), *up, *idx))?;
+        let mut common_ancestors: Vec<&Person> = a_ancestry
+            .iter()
+            .filter(|(idx, (a_up, _))| {
+                *a_up == up && b_ancestry.get(idx).is_some_and(|(b_up, _)| *b_up == down)
+            })
+            .map(|(idx, _)| &self.people_graph[*idx])
+            .collect();
+        common_ancestors.sort_by(|a, b| a.cmp(b).then(a.idx.cmp(&b.idx)));
+
+        let ancestor = common_ancestors[0].idx;
+        let mut path = chain(&a_ancestry, ancestor);
+        path.reverse();
+        path.extend(chain(&b_ancestry, ancestor).into_iter().skip(1));
+        let path: Vec<&Person> = path.iter().map(|idx| &self.people_graph[*idx]).collect();
+
+        // the children of the ancestor each line goes through
+        let half = up > 0
+            && down > 0
+            && self.sibling_kind(path[up - 1], path[up + 1]) != Some(SiblingKind::Full);
+        Some(Relationship {
+            name: relationship_name(up, down, half, &b.sex),
+            generations_up: up,
+            generations_down: down,
+            half,
+            common_ancestors,
+            path,
+        })
+    }
+
+    /// Goes up the parents breadth first, so each ancestor is reached by a shortest line
+    fn ancestry(&self, idx: usize) -> Ancestry {
+        let mut ancestry = Ancestry::new();
+        ancestry.insert(idx, (0, None));
+        let mut queue = VecDeque::from(vec![idx]);
+        while let Some(current) = queue.pop_front() {
+            let (generation, _) = ancestry[&current];
+            let person = &self.people_graph[current];
+            for parent in person.father.into_iter().chain(person.mother) {
+                if let Entry::Vacant(entry) = ancestry.entry(parent) {
+                    entry.insert((generation + 1, Some(current)));
+                    queue.push_back(parent);
+                }
+            }
+        }
+        ancestry
+    }
+}
+
+/// From `ancestor` down to the person the ancestry was computed for
+fn chain(ancestry: &Ancestry, ancestor: usize) -> Vec<usize> {
+    let mut chain = vec![ancestor];
+    let mut current = ancestor;
+    while let Some((_, Some(child))) = ancestry.get(&current) {
+        chain.push(*child);
+        current = *child;
+    }
+    chain
+}
+
+/// Name of the relationship of someone of sex `sex`, `down` generations below the common
+/// ancestors which are `up` generations above the other person
+fn relationship_name(up: usize, down: usize, half: bool, sex: &Sex) -> String {
+    let gendered = |male: &str, female: &str| match sex {
+        Sex::Male => male.to_string(),
+        Sex::Female => female.to_string(),
+    };
+    let half = if half { "half-" } else { "" };
+    match (up, down) {
+        (0, 0) => "self".to_string(),
+        (0, down) => greats(down, "grand", &gendered("son", "daughter")),
+        (up, 0) => greats(up, "grand", &gendered("father", "mother")),
+        (1, 1) => format!("{}{}", half, gendered("brother", "sister")),
+        (up, 1) => format!(
+            "{}{}",
+            half,
+            greats(up - 1, "great-", &gendered("uncle", "aunt"))
+        ),
+        (1, down) => format!(
+            "{}{}",
+            half,
+            greats(down - 1, "great-", &gendered("nephew", "niece"))
+        ),
+        (up, down) => {
+            let degree = up.min(down) - 1;
+            let removed = up.max(down) - up.min(down);
+            let removed = match removed {
+                0 => String::new(),
+                1 => " once removed".to_string(),
+                2 => " twice removed".to_string(),
+                n => format!(" {} times removed", n),
+            };
+            format!("{}{} cousin{}", half, ordinal(degree), removed)
+        }
+    }
+}
+
+/// `relative` for one generation, then with `second` ("grand" or "great-") and as many "great-"
+/// as needed before it: son, grandson, great-grandson...
+fn greats(generations: usize, second: &str, relative: &str) -> String {
+    match generations {
+        0 | 1 => relative.to_string(),
+        n => format!("{}{}{}", "great-".repeat(n - 2), second, relative),
+    }
+}
+
+fn ordinal(n: usize) -> String {
+    const ORDINALS: [&str; 10] = [
+        "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth",
+        "tenth",
+    ];
+    if let Some(ordinal) = ORDINALS.get(n - 1) {
+        return ordinal.to_string();
+    }
+    let suffix = match (n % 10, n % 100) {
+        (1, 11) | (2, 12) | (3, 13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
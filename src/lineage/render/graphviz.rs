@@ -0,0 +1,137 @@
+//! Graphviz DOT export of the lineage. Men are boxes and women ellipses, each edge going from a
+//! parent, or from the node of a couple, to a child.
+use super::{Drawing, GraphOptions};
+use crate::lineage::{Lineage, Person, Sex};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+impl Lineage {
+    /// The whole lineage in the DOT language, without any of the options
+    pub fn to_graphviz(&self) -> String {
+        self.to_graphviz_with(&GraphOptions::default())
+    }
+
+    /// The people of the lineage the options keep, in the DOT language, in insertion order
+    pub fn to_graphviz_with(&self, options: &GraphOptions) -> String {
+        let drawing = Drawing::new(self, options);
+        let people = &drawing.people;
+        let node = |person: &Person| {
+            let mut attributes = vec![
+                format!("label={}", quote(&person.name)),
+                match person.sex {
+                    Sex::Male => "shape=box".to_string(),
+                    Sex::Female => "shape=ellipse".to_string(),
+                },
+            ];
+            if options.grey_out_dead && !person.alive {
+                attributes.push(
+                    "style=\"filled,dashed\", fillcolor=lightgrey, fontcolor=grey40".to_string(),
+                );
+            }
+            if drawing.heir_of == Some(person.idx) {
+                attributes.push("penwidth=3".to_string());
+            }
+            if drawing.heir == Some(person.idx) {
+                attributes.push("penwidth=3, color=gold3".to_string());
+            }
+            format!("{} [{}];", quote(&person.id), attributes.join(", "))
+        };
+
+        let mut dot = String::from("digraph lineage {\n");
+        if options.house_clusters {
+            for (house, members) in drawing.houses() {
+                writeln!(
+                    dot,
+                    "    subgraph {} {{",
+                    quote(&format!("cluster_{}", house))
+                )
+                .unwrap();
+                writeln!(dot, "        label={};", quote(&format!("House {}", house))).unwrap();
+                for member in members {
+                    writeln!(dot, "        {}", node(member)).unwrap();
+                }
+                dot.push_str("    }\n");
+            }
+        } else {
+            for person in people {
+                writeln!(dot, "    {}", node(person)).unwrap();
+            }
+        }
+
+        let id = |idx: usize| quote(&self.people_graph[idx].id);
+        let mut couples = HashSet::new();
+        for child in people {
+            let parents = drawing.parents(child);
+            match (child.father, child.mother) {
+                (Some(father), Some(mother)) if options.couple_nodes && parents.len() == 2 => {
+                    let couple = quote(&format!(
+                        "{}+{}",
+                        self.people_graph[father].id, self.people_graph[mother].id
+                    ));
+                    if couples.insert((father, mother)) {
+                        writeln!(dot, "    {} [shape=point];", couple).unwrap();
+                        // drawn children of the couple
+                        let children: Vec<usize> = people
+                            .iter()
+                            .filter(|other| {
+                                other.father == Some(father) && other.mother == Some(mother)
+                            })
+                            .map(|other| other.idx)
+                            .collect();
+                        for parent in &parents {
+                            let highlighted = children
+                                .iter()
+                                .any(|child| drawing.on_heir_path(*parent, *child));
+                            writeln!(
+                                dot,
+                                "    {}",
+                                edge(&id(*parent), &couple, &["arrowhead=none"], highlighted)
+                            )
+                            .unwrap();
+                        }
+                    }
+                    let highlighted = parents
+                        .iter()
+                        .any(|parent| drawing.on_heir_path(*parent, child.idx));
+                    writeln!(
+                        dot,
+                        "    {}",
+                        edge(&couple, &id(child.idx), &[], highlighted)
+                    )
+                    .unwrap();
+                }
+                _ => {
+                    for parent in parents {
+                        let highlighted = drawing.on_heir_path(parent, child.idx);
+                        writeln!(
+                            dot,
+                            "    {}",
+                            edge(&id(parent), &id(child.idx), &[], highlighted)
+                        )
+                        .unwrap();
+                    }
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// DOT edge statement, in the color of the heir if `highlighted`
+fn edge(from: &str, to: &str, attributes: &[&str], highlighted: bool) -> String {
+    let mut attributes = attributes.to_vec();
+    if highlighted {
+        attributes.extend(&["color=gold3", "penwidth=2"]);
+    }
+    if attributes.is_empty() {
+        format!("{} -> {};", from, to)
+    } else {
+        format!("{} -> {} [{}];", from, to, attributes.join(", "))
+    }
+}
+
+/// DOT string literal
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
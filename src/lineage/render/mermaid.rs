@@ -0,0 +1,95 @@
+//! Mermaid flowchart export of the lineage, as rendered by Markdown wikis. Men are rectangles and
+//! women stadiums, each link going from a parent to a child.
+use super::{Drawing, GraphOptions, Identifiers};
+use crate::lineage::{Lineage, Person, Sex};
+use std::fmt::Write;
+
+impl Lineage {
+    /// The people of the lineage the options keep as a Mermaid flowchart, in insertion order
+    pub fn to_mermaid(&self, options: &GraphOptions) -> String {
+        let drawing = Drawing::new(self, options);
+        let ids = Identifiers::new(self);
+        let node = |person: &Person| {
+            let label = escape(&person.name);
+            match person.sex {
+                Sex::Male => format!("{}[\"{}\"]", ids.person(person.idx), label),
+                Sex::Female => format!("{}([\"{}\"])", ids.person(person.idx), label),
+            }
+        };
+
+        let mut mermaid = String::from("flowchart TD\n");
+        if options.house_clusters {
+            for (house, members) in drawing.houses() {
+                writeln!(
+                    mermaid,
+                    "    subgraph {}[\"House {}\"]",
+                    ids.house(house),
+                    escape(house)
+                )
+                .unwrap();
+                for member in members {
+                    writeln!(mermaid, "        {}", node(member)).unwrap();
+                }
+                mermaid.push_str("    end\n");
+            }
+        } else {
+            for person in &drawing.people {
+                writeln!(mermaid, "    {}", node(person)).unwrap();
+            }
+        }
+
+        // links are styled by their position
+        let mut heir_links = vec![];
+        let mut links = 0;
+        for child in &drawing.people {
+            for parent in drawing.parents(child) {
+                writeln!(
+                    mermaid,
+                    "    {} --> {}",
+                    ids.person(parent),
+                    ids.person(child.idx)
+                )
+                .unwrap();
+                if drawing.on_heir_path(parent, child.idx) {
+                    heir_links.push(links.to_string());
+                }
+                links += 1;
+            }
+        }
+
+        let dead: Vec<String> = drawing
+            .people
+            .iter()
+            .filter(|person| options.grey_out_dead && !person.alive)
+            .map(|person| ids.person(person.idx).to_string())
+            .collect();
+        if !dead.is_empty() {
+            mermaid.push_str("    classDef dead fill:#ddd,stroke-dasharray:4 3,color:#666\n");
+            writeln!(mermaid, "    class {} dead", dead.join(",")).unwrap();
+        }
+        if let Some(heir_of) = drawing.heir_of.filter(|heir_of| drawing.is_drawn(*heir_of)) {
+            mermaid.push_str("    classDef heirOf stroke-width:3px\n");
+            writeln!(mermaid, "    class {} heirOf", ids.person(heir_of)).unwrap();
+        }
+        if let Some(heir) = drawing.heir.filter(|heir| drawing.is_drawn(*heir)) {
+            mermaid.push_str("    classDef heir stroke:#d4a017,stroke-width:3px\n");
+            writeln!(mermaid, "    class {} heir", ids.person(heir)).unwrap();
+        }
+        if !heir_links.is_empty() {
+            writeln!(
+                mermaid,
+                "    linkStyle {} stroke:#d4a017,stroke-width:3px",
+                heir_links.join(",")
+            )
+            .unwrap();
+        }
+        mermaid
+    }
+}
+
+/// Text of a quoted Mermaid label, using its entity codes
+fn escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
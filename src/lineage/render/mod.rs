@@ -0,0 +1,174 @@
+//! Drawings of the lineage, or of a part of it, sharing the same options: Graphviz DOT, SVG,
+//! Mermaid and PlantUML, and the text tree of the descendants of a person
+mod graphviz;
+mod mermaid;
+mod plantuml;
+mod svg;
+mod text;
+
+use super::{Lineage, Person, PersonFilter};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// What the drawings show besides the people and their links
+#[derive(Debug, Clone, Default)]
+pub struct GraphOptions<'a> {
+    /// Groups the members of each house, in a cluster named after it for Graphviz, a subgraph
+    /// for Mermaid, a package for PlantUML and in a color of their own in SVG
+    pub house_clusters: bool,
+    /// Greys out the dead and draws them with a dashed border
+    pub grey_out_dead: bool,
+    /// Joins the father and the mother of a child in a couple node, linked once to each of
+    /// their children, instead of drawing an edge from each parent. Graphviz only, SVG always
+    /// draws couples side by side.
+    pub couple_nodes: bool,
+    /// Highlights this person, its current heir under the lineage law and the chain of
+    /// parent/child links between them
+    pub heir_of: Option<&'a Person>,
+    /// Only draws the people with these indexes, and the links between them
+    pub only: Option<HashSet<usize>>,
+    /// Only draws this person and its descendants
+    pub subtree_of: Option<&'a Person>,
+    /// Only draws the people matching it, e.g. the living members of a house
+    pub filter: PersonFilter,
+}
+
+/// The people drawn and what is highlighted, worked out once from the options
+struct Drawing<'a> {
+    /// In insertion order
+    people: Vec<&'a Person>,
+    heir_of: Option<usize>,
+    heir: Option<usize>,
+    /// Parent/child links from the person to its heir
+    heir_links: HashSet<(usize, usize)>,
+}
+
+impl<'a> Drawing<'a> {
+    fn new(lineage: &'a Lineage, options: &GraphOptions) -> Self {
+        let heir = options
+            .heir_of
+            .and_then(|person| lineage.succession_of(person, lineage.succession_law))
+            .map(|succession| succession.successor);
+        let mut heir_links = HashSet::new();
+        if let (Some(person), Some(heir)) = (options.heir_of, heir) {
+            let path = lineage.kinship_path(person, heir).unwrap_or_default();
+            for pair in path.windows(2) {
+                let (a, b) = (pair[0], pair[1]);
+                if b.father == Some(a.idx) || b.mother == Some(a.idx) {
+                    heir_links.insert((a.idx, b.idx));
+                } else {
+                    heir_links.insert((b.idx, a.idx));
+                }
+            }
+        }
+        let subtree: Option<HashSet<usize>> = options.subtree_of.map(|root| {
+            std::iter::once(root.idx)
+                .chain(
+                    lineage
+                        .descendants(root, None, PersonFilter::default())
+                        .map(|(descendant, _)| descendant.idx),
+                )
+                .collect()
+        });
+        let people = lineage
+            .people_graph
+            .iter()
+            .filter(|person| {
+                options
+                    .only
+                    .as_ref()
+                    .is_none_or(|only| only.contains(&person.idx))
+                    && subtree
+                        .as_ref()
+                        .is_none_or(|subtree| subtree.contains(&person.idx))
+                    && options.filter.matches(person)
+            })
+            .collect();
+        Drawing {
+            people,
+            heir_of: options.heir_of.map(|person| person.idx),
+            heir: heir.map(|heir| heir.idx),
+            heir_links,
+        }
+    }
+
+    fn is_drawn(&self, idx: usize) -> bool {
+        self.people
+            .binary_search_by_key(&idx, |person| person.idx)
+            .is_ok()
+    }
+
+    /// Father then mother of the person, the ones drawn
+    fn parents(&self, person: &Person) -> Vec<usize> {
+        person
+            .father
+            .into_iter()
+            .chain(person.mother)
+            .filter(|parent| self.is_drawn(*parent))
+            .collect()
+    }
+
+    fn on_heir_path(&self, parent: usize, child: usize) -> bool {
+        self.heir_links.contains(&(parent, child))
+    }
+
+    /// The people drawn by house, houses in alphabetical order
+    fn houses(&self) -> BTreeMap<&'a str, Vec<&'a Person>> {
+        let mut houses: BTreeMap<&str, Vec<&Person>> = BTreeMap::new();
+        for person in &self.people {
+            houses.entry(&person.house).or_default().push(person);
+        }
+        houses
+    }
+}
+
+/// Identifiers of the people and of the houses, made of letters, digits and underscores only as
+/// Mermaid and PlantUML expect them. Ids only differing by other characters ("a-b" and "a_b")
+/// would get the same one, so the later ones are suffixed with a number, houses coming first,
+/// then people in insertion order.
+struct Identifiers<'a> {
+    /// By person index
+    people: Vec<String>,
+    houses: HashMap<&'a str, String>,
+}
+
+impl<'a> Identifiers<'a> {
+    fn new(lineage: &'a Lineage) -> Self {
+        let mut used = HashSet::new();
+        let mut unique = |base: String| {
+            let mut identifier = base.clone();
+            let mut n = 2;
+            while !used.insert(identifier.clone()) {
+                identifier = format!("{}_{}", base, n);
+                n += 1;
+            }
+            identifier
+        };
+        let mut houses: Vec<&str> = lineage.houses_indexes.keys().map(String::as_str).collect();
+        houses.sort_unstable();
+        let houses = houses
+            .into_iter()
+            .map(|house| (house, unique(format!("house_{}", sanitize(house)))))
+            .collect();
+        let people = lineage
+            .people_graph
+            .iter()
+            .map(|person| unique(sanitize(&person.id)))
+            .collect();
+        Identifiers { people, houses }
+    }
+
+    fn person(&self, idx: usize) -> &str {
+        &self.people[idx]
+    }
+
+    fn house(&self, house: &str) -> &str {
+        &self.houses[house]
+    }
+}
+
+/// Replaces the characters Mermaid and PlantUML do not accept in identifiers with underscores
+fn sanitize(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
@@ -0,0 +1,73 @@
+//! PlantUML export of the lineage. Men are rectangles and women ellipses, each arrow going from a
+//! parent to a child.
+use super::{Drawing, GraphOptions, Identifiers};
+use crate::lineage::{Lineage, Person, Sex};
+use std::fmt::Write;
+
+impl Lineage {
+    /// The people of the lineage the options keep as a PlantUML diagram, in insertion order
+    pub fn to_plantuml(&self, options: &GraphOptions) -> String {
+        let drawing = Drawing::new(self, options);
+        let ids = Identifiers::new(self);
+        let element = |person: &Person| {
+            let mut style = vec![];
+            if options.grey_out_dead && !person.alive {
+                style.extend(&["DDDDDD", "line.dashed", "text:666666"]);
+            }
+            if drawing.heir == Some(person.idx) {
+                style.extend(&["line:D4A017", "line.bold"]);
+            } else if drawing.heir_of == Some(person.idx) {
+                style.push("line.bold");
+            }
+            format!(
+                "{} \"{}\" as {}{}",
+                match person.sex {
+                    Sex::Male => "rectangle",
+                    Sex::Female => "usecase",
+                },
+                // strings can't escape their quotes
+                person.name.replace('"', "'"),
+                ids.person(person.idx),
+                if style.is_empty() {
+                    String::new()
+                } else {
+                    format!(" #{}", style.join(";"))
+                }
+            )
+        };
+
+        let mut uml = String::from("@startuml\n");
+        if options.house_clusters {
+            for (house, members) in drawing.houses() {
+                writeln!(uml, "package \"House {}\" {{", house.replace('"', "'")).unwrap();
+                for member in members {
+                    writeln!(uml, "  {}", element(member)).unwrap();
+                }
+                uml.push_str("}\n");
+            }
+        } else {
+            for person in &drawing.people {
+                writeln!(uml, "{}", element(person)).unwrap();
+            }
+        }
+        for child in &drawing.people {
+            for parent in drawing.parents(child) {
+                let arrow = if drawing.on_heir_path(parent, child.idx) {
+                    "-[#D4A017,bold]->"
+                } else {
+                    "-->"
+                };
+                writeln!(
+                    uml,
+                    "{} {} {}",
+                    ids.person(parent),
+                    arrow,
+                    ids.person(child.idx)
+                )
+                .unwrap();
+            }
+        }
+        uml.push_str("@enduml\n");
+        uml
+    }
+}
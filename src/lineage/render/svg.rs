@@ -0,0 +1,352 @@
+//! SVG drawing of the lineage, laid out by generation without any external tool. Each generation
+//! is a row, spouses are side by side and children are placed under their parents as much as the
+//! room left allows.
+use super::{Drawing, GraphOptions};
+use crate::lineage::{Lineage, Person, Sex};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Write;
+
+const MARGIN: f64 = 20.0;
+const NODE_HEIGHT: f64 = 36.0;
+const MIN_NODE_WIDTH: f64 = 100.0;
+/// Rough width of a character of the 12px font, the text is not measured
+const CHAR_WIDTH: f64 = 7.0;
+const SPOUSE_GAP: f64 = 16.0;
+const NODE_GAP: f64 = 32.0;
+const ROW_GAP: f64 = 64.0;
+const HEIR_COLOR: &str = "#d4a017";
+
+/// Position of a person in the drawing
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    x: f64,
+    y: f64,
+    width: f64,
+}
+
+impl Node {
+    fn center(&self) -> f64 {
+        self.x + self.width / 2.0
+    }
+}
+
+impl Lineage {
+    /// The people of the lineage the options keep as an SVG document
+    pub fn to_svg(&self, options: &GraphOptions) -> String {
+        let drawing = Drawing::new(self, options);
+        let couples = couples(&drawing);
+        let rows = rows(&drawing, &couples);
+        let nodes = place(&drawing, &couples, &rows);
+
+        let width = nodes
+            .values()
+            .map(|node| node.x + node.width)
+            .fold(0.0, f64::max)
+            + MARGIN;
+        let height = rows.len() as f64 * (NODE_HEIGHT + ROW_GAP) - ROW_GAP + 2.0 * MARGIN;
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="12">"#,
+            w = width.max(2.0 * MARGIN),
+            h = height.max(2.0 * MARGIN)
+        )
+        .unwrap();
+
+        svg.push_str("  <g class=\"links\" fill=\"none\" stroke=\"#555\">\n");
+        for (father, mother) in &couples {
+            let (left, right) = match (nodes.get(father), nodes.get(mother)) {
+                (Some(a), Some(b)) if a.y == b.y => {
+                    if a.x < b.x {
+                        (a, b)
+                    } else {
+                        (b, a)
+                    }
+                }
+                _ => continue,
+            };
+            writeln!(
+                svg,
+                r#"    <path class="couple" d="M{} {} H{}"/>"#,
+                left.x + left.width,
+                left.y + NODE_HEIGHT / 2.0,
+                right.x
+            )
+            .unwrap();
+        }
+        for child in &drawing.people {
+            let child_node = nodes[&child.idx];
+            let parents = drawing.parents(child);
+            let highlighted = parents
+                .iter()
+                .any(|parent| drawing.on_heir_path(*parent, child.idx));
+            // from the middle of the line joining a couple, or from under a single parent
+            let starts: Vec<(f64, f64)> = match parents.as_slice() {
+                [father, mother] if couples.contains(&(*father, *mother)) => {
+                    let (a, b) = (nodes[father], nodes[mother]);
+                    if a.y == b.y {
+                        vec![((a.center() + b.center()) / 2.0, a.y + NODE_HEIGHT / 2.0)]
+                    } else {
+                        vec![
+                            (a.center(), a.y + NODE_HEIGHT),
+                            (b.center(), b.y + NODE_HEIGHT),
+                        ]
+                    }
+                }
+                _ => parents
+                    .iter()
+                    .map(|parent| (nodes[parent].center(), nodes[parent].y + NODE_HEIGHT))
+                    .collect(),
+            };
+            for (x, y) in starts {
+                let bend = child_node.y - ROW_GAP / 2.0;
+                let style = if highlighted {
+                    format!(r#" stroke="{}" stroke-width="3""#, HEIR_COLOR)
+                } else {
+                    String::new()
+                };
+                writeln!(
+                    svg,
+                    r#"    <path d="M{} {} V{} H{} V{}"{}/>"#,
+                    x,
+                    y,
+                    bend,
+                    child_node.center(),
+                    child_node.y,
+                    style
+                )
+                .unwrap();
+            }
+        }
+        svg.push_str("  </g>\n");
+
+        svg.push_str("  <g class=\"people\">\n");
+        for person in &drawing.people {
+            let node = nodes[&person.idx];
+            let mut fill = if options.house_clusters {
+                house_color(&person.house)
+            } else {
+                "#fff".to_string()
+            };
+            let mut stroke = "#333";
+            let mut extra = String::new();
+            let mut text_color = "#000";
+            if options.grey_out_dead && !person.alive {
+                fill = "#ddd".to_string();
+                text_color = "#666";
+                extra.push_str(r#" stroke-dasharray="4 3""#);
+            }
+            if drawing.heir == Some(person.idx) {
+                stroke = HEIR_COLOR;
+                extra.push_str(r#" stroke-width="3""#);
+            } else if drawing.heir_of == Some(person.idx) {
+                extra.push_str(r#" stroke-width="3""#);
+            }
+            // women have rounded corners
+            let radius = match person.sex {
+                Sex::Male => 0.0,
+                Sex::Female => NODE_HEIGHT / 2.0,
+            };
+            writeln!(
+                svg,
+                r#"    <g class="person" id="{}"><title>{}</title>"#,
+                escape(&person.id),
+                escape(&person.name)
+            )
+            .unwrap();
+            writeln!(
+                svg,
+                r#"      <rect x="{}" y="{}" width="{}" height="{}" rx="{}" fill="{}" stroke="{}"{}/>"#,
+                node.x, node.y, node.width, NODE_HEIGHT, radius, fill, stroke, extra
+            )
+            .unwrap();
+            writeln!(
+                svg,
+                r#"      <text x="{}" y="{}" text-anchor="middle" dominant-baseline="middle" fill="{}">{}</text>"#,
+                node.center(),
+                node.y + NODE_HEIGHT / 2.0,
+                text_color,
+                escape(&person.name)
+            )
+            .unwrap();
+            svg.push_str("    </g>\n");
+        }
+        svg.push_str("  </g>\n</svg>\n");
+        svg
+    }
+}
+
+/// Father and mother of the children drawn with both their parents, in insertion order
+fn couples(drawing: &Drawing) -> Vec<(usize, usize)> {
+    let mut couples = vec![];
+    for child in &drawing.people {
+        if let [father, mother] = drawing.parents(child).as_slice() {
+            if !couples.contains(&(*father, *mother)) {
+                couples.push((*father, *mother));
+            }
+        }
+    }
+    couples
+}
+
+/// The generation row of each person: children are below their parents and spouses on the same
+/// row, people married into the family going down to the row of their spouse
+fn rows(drawing: &Drawing, couples: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let mut row: HashMap<usize, usize> = drawing.people.iter().map(|p| (p.idx, 0)).collect();
+    // rows only go down, a marriage between generations could otherwise push them forever
+    for _ in 0..=drawing.people.len() {
+        let mut changed = false;
+        for child in &drawing.people {
+            for parent in drawing.parents(child) {
+                if row[&child.idx] < row[&parent] + 1 {
+                    row.insert(child.idx, row[&parent] + 1);
+                    changed = true;
+                }
+            }
+        }
+        for (father, mother) in couples {
+            let lowest = row[father].max(row[mother]);
+            for spouse in &[father, mother] {
+                if row[*spouse] != lowest {
+                    row.insert(**spouse, lowest);
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    let count = row.values().max().map_or(0, |max| max + 1);
+    let mut rows = vec![vec![]; count];
+    for person in &drawing.people {
+        rows[row[&person.idx]].push(person.idx);
+    }
+    rows
+}
+
+/// Positions of the people, row by row from the top. Spouses are grouped in a unit and each unit
+/// is centered under the parents of its members, shifted right when it would overlap the
+/// previous one.
+fn place(
+    drawing: &Drawing,
+    couples: &[(usize, usize)],
+    rows: &[Vec<usize>],
+) -> HashMap<usize, Node> {
+    let people: HashMap<usize, &Person> = drawing.people.iter().map(|p| (p.idx, *p)).collect();
+    let node_width = |idx: usize| {
+        (people[&idx].name.chars().count() as f64 * CHAR_WIDTH + 2.0 * CHAR_WIDTH)
+            .max(MIN_NODE_WIDTH)
+    };
+    let mut nodes: HashMap<usize, Node> = HashMap::new();
+    for (row_number, row) in rows.iter().enumerate() {
+        let y = MARGIN + row_number as f64 * (NODE_HEIGHT + ROW_GAP);
+        let units = spouse_units(row, couples);
+        // where the unit would like to be: under the parents of its members
+        let mut wanted: Vec<(Option<f64>, Vec<usize>)> = units
+            .into_iter()
+            .map(|unit| {
+                let anchors: Vec<f64> = unit
+                    .iter()
+                    .filter_map(|member| {
+                        let placed: Vec<f64> = drawing
+                            .parents(people[member])
+                            .iter()
+                            .filter_map(|parent| nodes.get(parent).map(Node::center))
+                            .collect();
+                        if placed.is_empty() {
+                            None
+                        } else {
+                            Some(placed.iter().sum::<f64>() / placed.len() as f64)
+                        }
+                    })
+                    .collect();
+                let anchor = if anchors.is_empty() {
+                    None
+                } else {
+                    Some(anchors.iter().sum::<f64>() / anchors.len() as f64)
+                };
+                (anchor, unit)
+            })
+            .collect();
+        // units without parents keep their order, after the others
+        wanted.sort_by(|(a, _), (b, _)| {
+            a.unwrap_or(f64::INFINITY)
+                .partial_cmp(&b.unwrap_or(f64::INFINITY))
+                .expect("anchors are not NaN")
+        });
+        let mut cursor = MARGIN;
+        for (anchor, unit) in wanted {
+            let widths: Vec<f64> = unit.iter().map(|member| node_width(*member)).collect();
+            let unit_width = widths.iter().sum::<f64>() + SPOUSE_GAP * (unit.len() as f64 - 1.0);
+            let mut x = anchor.map_or(cursor, |anchor| (anchor - unit_width / 2.0).max(cursor));
+            for (member, width) in unit.iter().zip(widths) {
+                nodes.insert(*member, Node { x, y, width });
+                x += width + SPOUSE_GAP;
+            }
+            cursor = x - SPOUSE_GAP + NODE_GAP;
+        }
+    }
+    nodes
+}
+
+/// The people of a row grouped with their spouses of the same row, a person with several
+/// spouses being between them when it can
+fn spouse_units(row: &[usize], couples: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let in_row: HashSet<usize> = row.iter().cloned().collect();
+    let spouses_of = |idx: usize| -> Vec<usize> {
+        couples
+            .iter()
+            .filter_map(|(father, mother)| match (*father == idx, *mother == idx) {
+                (true, _) => Some(*mother),
+                (_, true) => Some(*father),
+                _ => None,
+            })
+            .filter(|spouse| in_row.contains(spouse))
+            .collect()
+    };
+    let mut placed = HashSet::new();
+    let mut units = vec![];
+    for idx in row {
+        if placed.contains(idx) {
+            continue;
+        }
+        // the whole group of people linked by marriages, walked from this person in another
+        // order than the one they are drawn in
+        let mut unit: Vec<usize> = vec![*idx];
+        placed.insert(*idx);
+        let mut to_walk = VecDeque::from(vec![*idx]);
+        while let Some(current) = to_walk.pop_front() {
+            let spouses = spouses_of(current);
+            for spouse in &spouses {
+                if placed.insert(*spouse) {
+                    // the first spouse of the first person goes on its left
+                    if current == *idx && unit.len() == 1 && spouses.len() > 1 {
+                        unit.insert(0, *spouse);
+                    } else {
+                        unit.push(*spouse);
+                    }
+                    to_walk.push_back(*spouse);
+                }
+            }
+        }
+        units.push(unit);
+    }
+    units
+}
+
+/// A light color of its own for each house
+fn house_color(house: &str) -> String {
+    let hue = house.bytes().fold(0u32, |hash, byte| {
+        hash.wrapping_mul(31).wrapping_add(byte as u32)
+    }) % 360;
+    format!("hsl({}, 60%, 88%)", hue)
+}
+
+/// Text and attribute values of an XML document
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
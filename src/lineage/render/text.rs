@@ -0,0 +1,73 @@
+//! Descendants of a person as a tree drawn with box-drawing characters, for terminals
+use crate::lineage::{Lineage, Person};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+impl Lineage {
+    /// The person and its descendants down to `max_depth` generations (all of them when None),
+    /// one per line, sons before daughters. The dead are followed by †, and the heir of the
+    /// person under the lineage law by an arrow. Someone descending from the person through both
+    /// parents is only expanded the first time.
+    pub fn to_text_tree(&self, person: &Person, max_depth: Option<usize>) -> String {
+        let heir = self
+            .succession_of(person, self.succession_law)
+            .map(|succession| succession.successor.idx);
+        let label = |person: &Person| {
+            let mut label = person.name.clone();
+            if !person.alive {
+                label.push_str(" †");
+            }
+            if heir == Some(person.idx) {
+                label.push_str(" ← heir");
+            }
+            label
+        };
+        let children = |person: &Person| -> Vec<usize> {
+            person
+                .sons
+                .iter()
+                .chain(person.daughters.iter())
+                .cloned()
+                .collect()
+        };
+
+        let mut text = label(person);
+        text.push('\n');
+        let mut visited = HashSet::new();
+        visited.insert(person.idx);
+        // kept on a stack rather than recursing, the lineage can be arbitrarily deep
+        let mut stack: Vec<(usize, String, bool, usize)> = vec![];
+        let push_children = |stack: &mut Vec<_>, person: &Person, prefix: &str, depth: usize| {
+            if max_depth.is_none_or(|max_depth| depth < max_depth) {
+                let children = children(person);
+                for (i, child) in children.iter().enumerate().rev() {
+                    stack.push((
+                        *child,
+                        prefix.to_string(),
+                        i + 1 == children.len(),
+                        depth + 1,
+                    ));
+                }
+            }
+        };
+        push_children(&mut stack, person, "", 0);
+        while let Some((idx, prefix, last, depth)) = stack.pop() {
+            let child = &self.people_graph[idx];
+            let first_visit = visited.insert(idx);
+            writeln!(
+                text,
+                "{}{}{}{}",
+                prefix,
+                if last { "└── " } else { "├── " },
+                label(child),
+                if first_visit { "" } else { " (see above)" }
+            )
+            .unwrap();
+            if first_visit {
+                let prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+                push_children(&mut stack, child, &prefix, depth);
+            }
+        }
+        text
+    }
+}
@@ -0,0 +1,116 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A group of relatives considered together when looking for an heir. Inside a tier the
+/// alphabetical order is used to break ties.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SuccessionTier {
+    Sons,
+    Brothers,
+    Nephews,
+    Daughters,
+    Sisters,
+    Nieces,
+    /// Brothers having the same father and mother
+    FullBrothers,
+    /// Brothers having the same father only
+    PaternalHalfBrothers,
+    /// Brothers having the same mother only
+    MaternalHalfBrothers,
+    /// Sisters having the same father and mother
+    FullSisters,
+    /// Sisters having the same father only
+    PaternalHalfSisters,
+    /// Sisters having the same mother only
+    MaternalHalfSisters,
+    /// Sons and daughters together
+    Children,
+    /// Brothers and sisters together
+    Siblings,
+    /// Nephews and nieces together
+    SiblingsChildren,
+    /// Any remaining member of the house
+    House,
+}
+
+/// The set of rules used to pick the heir of a person, expressed as an ordered list of tiers.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum SuccessionLaw {
+    /// The traditional Westerosi rules: men of each tier come before the women of any tier
+    #[default]
+    MalePreference,
+    /// The first born inherits regardless of sex, so sons and daughters share the same tier
+    AbsolutePrimogeniture,
+    /// Male preference where full siblings come before paternal half-siblings, and maternal
+    /// half-siblings, born into another house, do not inherit as siblings. Their children still
+    /// inherit as nephews and nieces, after the children of the other siblings.
+    FullBloodPreference,
+}
+
+impl SuccessionTier {
+    /// Whether the tier is made of brothers or sisters of the person
+    pub fn is_siblings_tier(self) -> bool {
+        use SuccessionTier::*;
+        match self {
+            Brothers | Sisters | Siblings | FullBrothers | PaternalHalfBrothers
+            | MaternalHalfBrothers | FullSisters | PaternalHalfSisters | MaternalHalfSisters => {
+                true
+            }
+            Sons | Nephews | Daughters | Nieces | Children | SiblingsChildren | House => false,
+        }
+    }
+}
+
+impl SuccessionLaw {
+    pub fn tiers(self) -> &'static [SuccessionTier] {
+        use SuccessionTier::*;
+        match self {
+            SuccessionLaw::MalePreference => {
+                &[Sons, Brothers, Nephews, Daughters, Sisters, Nieces, House]
+            }
+            SuccessionLaw::AbsolutePrimogeniture => &[Children, Siblings, SiblingsChildren, House],
+            SuccessionLaw::FullBloodPreference => &[
+                Sons,
+                FullBrothers,
+                PaternalHalfBrothers,
+                Nephews,
+                Daughters,
+                FullSisters,
+                PaternalHalfSisters,
+                Nieces,
+                House,
+            ],
+        }
+    }
+}
+
+impl fmt::Display for SuccessionLaw {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SuccessionLaw::MalePreference => write!(f, "male-preference"),
+            SuccessionLaw::AbsolutePrimogeniture => write!(f, "absolute-primogeniture"),
+            SuccessionLaw::FullBloodPreference => write!(f, "full-blood-preference"),
+        }
+    }
+}
+
+impl FromStr for SuccessionLaw {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "male-preference" => Ok(SuccessionLaw::MalePreference),
+            "absolute-primogeniture" => Ok(SuccessionLaw::AbsolutePrimogeniture),
+            "full-blood-preference" => Ok(SuccessionLaw::FullBloodPreference),
+            other => Err(format!(
+                "unknown succession law {}, expected male-preference, absolute-primogeniture or \
+                 full-blood-preference",
+                other
+            )),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::lineage::{
-    Lineage, ParentChildInfo,
+    Lineage, ParentChildInfo, Person,
     Sex::{Female, Male},
+    SiblingKind,
 };
 
 #[test]
@@ -149,3 +150,167 @@ fn can_get_nephew_and_niece() {
     assert_eq!(niece.len(), 1);
     assert_eq!(niece.first().unwrap().name, niece_name);
 }
+
+/// Creates the following lineage, Half X being the son of Father X and Other W
+///
+///                 (Grandfather X + Grandmother X)
+///                 /              |             \
+///   (Mother Z + Father X + Other W)        Uncle X       Aunt X
+///        /        \          \                |
+///      Me X     Sister X    Half X         Cousin X
+///       |                    |                |
+///     Son X             Half-Son X      Cousin-Son X
+fn create_extended_family() -> Lineage {
+    let mut lineage = Lineage::new();
+    for (parent, parent_sex, child, child_sex) in &[
+        ("Grandfather X", Male, "Father X", Male),
+        ("Grandmother X", Female, "Father X", Male),
+        ("Grandfather X", Male, "Uncle X", Male),
+        ("Grandmother X", Female, "Uncle X", Male),
+        ("Grandfather X", Male, "Aunt X", Female),
+        ("Grandmother X", Female, "Aunt X", Female),
+        ("Father X", Male, "Me X", Male),
+        ("Mother Z", Female, "Me X", Male),
+        ("Father X", Male, "Sister X", Female),
+        ("Mother Z", Female, "Sister X", Female),
+        ("Father X", Male, "Half X", Male),
+        ("Other W", Female, "Half X", Male),
+        ("Uncle X", Male, "Cousin X", Male),
+        ("Me X", Male, "Son X", Male),
+        ("Half X", Male, "Half-Son X", Male),
+        ("Cousin X", Male, "Cousin-Son X", Male),
+    ] {
+        lineage.insert(ParentChildInfo::new(
+            *parent,
+            parent_sex.clone(),
+            *child,
+            child_sex.clone(),
+        ));
+    }
+    lineage
+}
+
+fn sorted_names(mut people: Vec<&Person>) -> Vec<&str> {
+    people.sort();
+    people.into_iter().map(|person| person.name()).collect()
+}
+
+#[test]
+fn can_get_grandparents_and_grandchildren() {
+    let lineage = create_extended_family();
+    let me = lineage.get_from_name("Me X").unwrap();
+    assert_eq!(
+        sorted_names(lineage.get_grandparents(me)),
+        vec!["Grandfather X", "Grandmother X"]
+    );
+    let son = lineage.get_from_name("Son X").unwrap();
+    assert_eq!(
+        sorted_names(lineage.get_grandparents(son)),
+        vec!["Father X", "Mother Z"]
+    );
+    let grandmother = lineage.get_from_name("Grandmother X").unwrap();
+    assert_eq!(
+        sorted_names(lineage.get_grandchildren(grandmother)),
+        vec!["Cousin X", "Half X", "Me X", "Sister X"]
+    );
+    assert!(lineage.get_grandparents(grandmother).is_empty());
+}
+
+#[test]
+fn can_get_uncles_and_aunts() {
+    let lineage = create_extended_family();
+    let me = lineage.get_from_name("Me X").unwrap();
+    assert_eq!(sorted_names(lineage.get_uncles(me)), vec!["Uncle X"]);
+    assert_eq!(sorted_names(lineage.get_aunts(me)), vec!["Aunt X"]);
+    // the half-brother of the father is an uncle too
+    let son = lineage.get_from_name("Son X").unwrap();
+    assert_eq!(sorted_names(lineage.get_uncles(son)), vec!["Half X"]);
+    assert_eq!(sorted_names(lineage.get_aunts(son)), vec!["Sister X"]);
+}
+
+#[test]
+fn can_get_full_and_half_siblings() {
+    let lineage = create_extended_family();
+    let me = lineage.get_from_name("Me X").unwrap();
+    assert_eq!(
+        sorted_names(lineage.get_full_siblings(me)),
+        vec!["Sister X"]
+    );
+    assert_eq!(sorted_names(lineage.get_half_siblings(me)), vec!["Half X"]);
+    let half = lineage.get_from_name("Half X").unwrap();
+    assert!(lineage.get_full_siblings(half).is_empty());
+    assert_eq!(
+        sorted_names(lineage.get_half_siblings(half)),
+        vec!["Me X", "Sister X"]
+    );
+    // with a single known parent nobody is known to be a full sibling
+    let cousin = lineage.get_from_name("Cousin X").unwrap();
+    assert!(lineage.get_full_siblings(cousin).is_empty());
+    assert!(lineage.get_half_siblings(cousin).is_empty());
+}
+
+#[test]
+fn can_get_cousins_of_any_degree() {
+    let lineage = create_extended_family();
+    let me = lineage.get_from_name("Me X").unwrap();
+    assert_eq!(sorted_names(lineage.get_cousins(me, 1)), vec!["Cousin X"]);
+    assert_eq!(
+        sorted_names(lineage.get_cousins(me, 0)),
+        vec!["Half X", "Sister X"]
+    );
+    let son = lineage.get_from_name("Son X").unwrap();
+    // children of a half-uncle are first cousins, children of a first cousin of the father are
+    // second cousins
+    assert_eq!(
+        sorted_names(lineage.get_cousins(son, 1)),
+        vec!["Half-Son X"]
+    );
+    assert_eq!(
+        sorted_names(lineage.get_cousins(son, 2)),
+        vec!["Cousin-Son X"]
+    );
+    assert!(lineage.get_cousins(son, 3).is_empty());
+}
+
+#[test]
+fn can_tell_sibling_kinds() {
+    let mut lineage = create_extended_family();
+    lineage.insert(ParentChildInfo::new(
+        "Mother Z",
+        Female,
+        "Maternal Z",
+        Female,
+    ));
+    let me = lineage.get_from_name("Me X").unwrap();
+    let mut siblings: Vec<(&str, SiblingKind)> = lineage
+        .get_siblings_with_kind(me)
+        .into_iter()
+        .map(|(sibling, kind)| (sibling.name(), kind))
+        .collect();
+    siblings.sort_by_key(|(name, _)| *name);
+    assert_eq!(
+        siblings,
+        vec![
+            ("Half X", SiblingKind::Paternal),
+            ("Maternal Z", SiblingKind::Maternal),
+            ("Sister X", SiblingKind::Full),
+        ]
+    );
+    let maternal = lineage.get_from_name("Maternal Z").unwrap();
+    let half = lineage.get_from_name("Half X").unwrap();
+    assert_eq!(lineage.sibling_kind(maternal, half), None);
+    assert_eq!(lineage.sibling_kind(me, me), None);
+    // a single known parent in common is not enough to be full siblings
+    lineage.insert(ParentChildInfo::new(
+        "Uncle X",
+        Male,
+        "Other-Cousin X",
+        Male,
+    ));
+    let cousin = lineage.get_from_name("Cousin X").unwrap();
+    let other_cousin = lineage.get_from_name("Other-Cousin X").unwrap();
+    assert_eq!(
+        lineage.sibling_kind(cousin, other_cousin),
+        Some(SiblingKind::Paternal)
+    );
+}
@@ -0,0 +1,157 @@
+use crate::lineage::{
+    Lineage, ParentChildInfo, Person, PersonFilter,
+    Sex::{self, Female, Male},
+};
+
+/// Creates the following lineage, Cousin-Husband X marrying Cousin X, his father's niece
+///
+///            (Grandfather X + Grandmother X)
+///              /                      \
+///       (Father X + Mother Z)      (Uncle X + Aunt Y)
+///        /        \                      |
+///      Me X     Sister X     (Cousin X + Cousin-Husband X)
+///                                        |
+///                                   Child X
+fn create_lineage() -> Lineage {
+    let mut lineage = Lineage::new();
+    for (parent, parent_sex, child, child_sex) in &[
+        ("Grandfather X", Male, "Father X", Male),
+        ("Grandmother X", Female, "Father X", Male),
+        ("Grandfather X", Male, "Uncle X", Male),
+        ("Grandmother X", Female, "Uncle X", Male),
+        ("Father X", Male, "Me X", Male),
+        ("Mother Z", Female, "Me X", Male),
+        ("Father X", Male, "Sister X", Female),
+        ("Mother Z", Female, "Sister X", Female),
+        ("Uncle X", Male, "Cousin X", Female),
+        ("Aunt Y", Female, "Cousin X", Female),
+        ("Father X", Male, "Cousin-Husband X", Male),
+        ("Cousin X", Female, "Child X", Male),
+        ("Cousin-Husband X", Male, "Child X", Male),
+    ] {
+        lineage.insert(ParentChildInfo::new(
+            *parent,
+            parent_sex.clone(),
+            *child,
+            child_sex.clone(),
+        ));
+    }
+    lineage
+}
+
+fn names<'a>(people: impl Iterator<Item = (&'a Person, usize)>) -> Vec<(&'a str, usize)> {
+    people
+        .map(|(person, generation)| (person.name(), generation))
+        .collect()
+}
+
+#[test]
+fn ancestors_generation_by_generation() {
+    let lineage = create_lineage();
+    let child = lineage.get_from_name("Child X").unwrap();
+    // Father X and the grandparents are reached through both parents but only returned once
+    assert_eq!(
+        names(lineage.ancestors(child, None)),
+        vec![
+            ("Cousin-Husband X", 1),
+            ("Cousin X", 1),
+            ("Father X", 2),
+            ("Uncle X", 2),
+            ("Aunt Y", 2),
+            ("Grandfather X", 3),
+            ("Grandmother X", 3),
+        ]
+    );
+    assert_eq!(
+        names(lineage.ancestors(child, Some(1))),
+        vec![("Cousin-Husband X", 1), ("Cousin X", 1)]
+    );
+    assert!(lineage.ancestors(child, Some(0)).next().is_none());
+    let grandfather = lineage.get_from_name("Grandfather X").unwrap();
+    assert!(lineage.ancestors(grandfather, None).next().is_none());
+}
+
+#[test]
+fn descendants_generation_by_generation() {
+    let lineage = create_lineage();
+    let grandmother = lineage.get_from_name("Grandmother X").unwrap();
+    assert_eq!(
+        names(lineage.descendants(grandmother, None, PersonFilter::default())),
+        vec![
+            ("Father X", 1),
+            ("Uncle X", 1),
+            ("Me X", 2),
+            ("Cousin-Husband X", 2),
+            ("Sister X", 2),
+            ("Cousin X", 2),
+            ("Child X", 3),
+        ]
+    );
+    assert_eq!(
+        names(lineage.descendants(grandmother, Some(1), PersonFilter::default())),
+        vec![("Father X", 1), ("Uncle X", 1)]
+    );
+}
+
+#[test]
+fn descendants_filtered() {
+    let mut lineage = create_lineage();
+    lineage.kill("Father X").unwrap();
+    lineage.kill("Me X").unwrap();
+    let grandfather = lineage.get_from_name("Grandfather X").unwrap();
+    let filtered = |filter: PersonFilter| {
+        names(lineage.descendants(grandfather, None, filter))
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>()
+    };
+    // the dead still lead to their living descendants
+    assert_eq!(
+        filtered(PersonFilter {
+            alive: Some(true),
+            sex: Some(Male),
+            ..PersonFilter::default()
+        }),
+        vec!["Uncle X", "Cousin-Husband X", "Child X"]
+    );
+    assert_eq!(
+        filtered(PersonFilter {
+            alive: Some(false),
+            ..PersonFilter::default()
+        }),
+        vec!["Father X", "Me X"]
+    );
+    assert_eq!(
+        filtered(PersonFilter {
+            house: Some("Z".to_string()),
+            ..PersonFilter::default()
+        }),
+        Vec::<&str>::new()
+    );
+}
+
+#[test]
+fn deep_lineages_do_not_overflow_the_stack() {
+    const GENERATIONS: usize = 20_000;
+    let mut lineage = Lineage::new();
+    let name = |generation: usize| format!("Aegon{} Targaryen", generation);
+    // from the youngest up, so each new parent has no ancestors to check for cycles
+    for generation in (1..=GENERATIONS).rev() {
+        lineage.insert(ParentChildInfo::new(
+            name(generation - 1),
+            Sex::Male,
+            name(generation),
+            Sex::Male,
+        ));
+    }
+    let first = lineage.get_from_name(&name(0)).unwrap();
+    let last = lineage.get_from_name(&name(GENERATIONS)).unwrap();
+    assert_eq!(lineage.ancestors(last, None).count(), GENERATIONS);
+    assert_eq!(
+        lineage
+            .descendants(first, None, PersonFilter::default())
+            .last()
+            .map(|(person, generation)| (person.name(), generation)),
+        Some((name(GENERATIONS).as_str(), GENERATIONS))
+    );
+}
@@ -0,0 +1,138 @@
+use crate::lineage::{
+    GraphOptions, Lineage, ParentChildInfo, Person,
+    Sex::{Female, Male},
+};
+
+/// Creates the following lineage
+///
+///   (Father X + Mother Y)
+///     /        \
+///   Son X    Daughter X
+fn create_lineage() -> Lineage {
+    let mut lineage = Lineage::new();
+    for (parent, parent_sex, child, child_sex) in &[
+        ("Father X", Male, "Son X", Male),
+        ("Mother Y", Female, "Son X", Male),
+        ("Father X", Male, "Daughter X", Female),
+        ("Mother Y", Female, "Daughter X", Female),
+    ] {
+        lineage.insert(ParentChildInfo::new(
+            *parent,
+            parent_sex.clone(),
+            *child,
+            child_sex.clone(),
+        ));
+    }
+    lineage
+}
+
+#[test]
+fn plain_graph_links_each_parent() {
+    let lineage = create_lineage();
+    let dot = lineage.to_graphviz();
+    assert!(dot.starts_with("digraph lineage {\n"));
+    assert!(dot.contains(r#"    "son-x" [label="Son X", shape=box];"#));
+    assert!(dot.contains(r#"    "mother-y" [label="Mother Y", shape=ellipse];"#));
+    assert!(dot.contains(r#"    "father-x" -> "son-x";"#));
+    assert!(dot.contains(r#"    "mother-y" -> "daughter-x";"#));
+    assert!(!dot.contains("subgraph"));
+    assert!(!dot.contains("point"));
+    assert!(dot.ends_with("}\n"));
+}
+
+#[test]
+fn houses_are_clusters() {
+    let lineage = create_lineage();
+    let dot = lineage.to_graphviz_with(&GraphOptions {
+        house_clusters: true,
+        ..GraphOptions::default()
+    });
+    let x = dot.find(r#"subgraph "cluster_X" {"#).unwrap();
+    let y = dot.find(r#"subgraph "cluster_Y" {"#).unwrap();
+    assert!(x < y);
+    assert!(dot.contains(r#"        label="House X";"#));
+    // members are inside the cluster of their house
+    let son = dot.find(r#"        "son-x""#).unwrap();
+    let mother = dot.find(r#"        "mother-y""#).unwrap();
+    assert!(x < son && son < y && y < mother);
+}
+
+#[test]
+fn couples_are_drawn_once() {
+    let lineage = create_lineage();
+    let dot = lineage.to_graphviz_with(&GraphOptions {
+        couple_nodes: true,
+        ..GraphOptions::default()
+    });
+    assert_eq!(dot.matches("[shape=point]").count(), 1);
+    assert!(dot.contains(r#"    "father-x" -> "father-x+mother-y" [arrowhead=none];"#));
+    assert!(dot.contains(r#"    "mother-y" -> "father-x+mother-y" [arrowhead=none];"#));
+    assert!(dot.contains(r#"    "father-x+mother-y" -> "son-x";"#));
+    assert!(dot.contains(r#"    "father-x+mother-y" -> "daughter-x";"#));
+    assert!(!dot.contains(r#""father-x" -> "son-x""#));
+}
+
+#[test]
+fn dead_are_greyed_out_and_heir_highlighted() {
+    let mut lineage = create_lineage();
+    lineage.kill("Son X").unwrap();
+    let father = lineage.get_from_name("Father X").unwrap();
+    let dot = lineage.to_graphviz_with(&GraphOptions {
+        grey_out_dead: true,
+        heir_of: Some(father),
+        ..GraphOptions::default()
+    });
+    assert!(dot.contains(
+        r#""son-x" [label="Son X", shape=box, style="filled,dashed", fillcolor=lightgrey"#
+    ));
+    assert!(dot.contains(r#""father-x" [label="Father X", shape=box, penwidth=3];"#));
+    assert!(dot
+        .contains(r#""daughter-x" [label="Daughter X", shape=ellipse, penwidth=3, color=gold3];"#));
+    // without the option the dead look like the living
+    assert!(!lineage.to_graphviz().contains("lightgrey"));
+}
+
+#[test]
+fn draws_only_the_given_people_with_the_heir_path() {
+    let mut lineage = create_lineage();
+    lineage.insert(ParentChildInfo::new("Son X", Male, "Grandson X", Male));
+    let son = lineage.get_from_name("Son X").unwrap();
+    let only = lineage
+        .neighbourhood(son, 0, 1, false)
+        .into_iter()
+        .map(|person| person.idx())
+        .collect();
+    let dot = lineage.to_graphviz_with(&GraphOptions {
+        heir_of: Some(son),
+        only: Some(only),
+        ..GraphOptions::default()
+    });
+    assert!(
+        dot.contains(r#""grandson-x" [label="Grandson X", shape=box, penwidth=3, color=gold3];"#)
+    );
+    assert!(dot.contains(r#"    "son-x" -> "grandson-x" [color=gold3, penwidth=2];"#));
+    // links to people not drawn are left out
+    assert!(!dot.contains("father-x"));
+    assert!(!dot.contains("daughter-x"));
+}
+
+#[test]
+fn neighbourhood_goes_up_and_down() {
+    let mut lineage = create_lineage();
+    lineage.insert(ParentChildInfo::new("Son X", Male, "Grandson X", Male));
+    let son = lineage.get_from_name("Son X").unwrap();
+    let names = |people: Vec<&Person>| -> Vec<String> {
+        people
+            .iter()
+            .map(|person| person.name().to_string())
+            .collect()
+    };
+    assert_eq!(
+        names(lineage.neighbourhood(son, 1, 1, false)),
+        vec!["Son X", "Father X", "Mother Y", "Grandson X"]
+    );
+    assert_eq!(
+        names(lineage.neighbourhood(son, 0, 0, true)),
+        vec!["Son X", "Daughter X"]
+    );
+}
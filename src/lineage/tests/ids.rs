@@ -0,0 +1,133 @@
+use crate::lineage::{InsertError, KillError, Lineage, ParentChildInfo, ResolveError, Sex::Male};
+
+/// Eddard Stark named his son after his brother Brandon
+fn create_lineage() -> Lineage {
+    let mut lineage = Lineage::new();
+    lineage.insert(
+        ParentChildInfo::new("Rickard Stark", Male, "Eddard Stark", Male)
+            .with_ids("rickard-stark", "eddard-stark"),
+    );
+    lineage.insert(
+        ParentChildInfo::new("Rickard Stark", Male, "Brandon Stark", Male)
+            .with_ids("rickard-stark", "brandon-stark"),
+    );
+    lineage.insert(
+        ParentChildInfo::new("Eddard Stark", Male, "Brandon Stark", Male)
+            .with_ids("eddard-stark", "bran-stark"),
+    );
+    lineage
+}
+
+#[test]
+fn homonyms_are_told_apart_by_id() {
+    let lineage = create_lineage();
+    let brandon = lineage.get_from_id("brandon-stark").unwrap();
+    let bran = lineage.get_from_id("bran-stark").unwrap();
+    assert_eq!(brandon.name(), bran.name());
+    assert_eq!(
+        lineage.get_father_of(brandon).unwrap().id(),
+        "rickard-stark"
+    );
+    assert_eq!(lineage.get_father_of(bran).unwrap().id(), "eddard-stark");
+    assert_eq!(lineage.get_from_name("Brandon Stark"), None);
+    assert_eq!(
+        lineage.next_in_line("eddard-stark").unwrap().id(),
+        "bran-stark"
+    );
+}
+
+#[test]
+fn ambiguous_names_list_the_candidates() {
+    let mut lineage = create_lineage();
+    match lineage.resolve("brandon stark") {
+        Err(ResolveError::Ambiguous(ambiguous)) => assert_eq!(
+            ambiguous.candidates,
+            vec!["brandon-stark".to_string(), "bran-stark".to_string()]
+        ),
+        resolved => panic!("Brandon Stark is not ambiguous: {:?}", resolved),
+    }
+    assert_eq!(
+        lineage.kill("Brandon Stark"),
+        Err(KillError::PersonNotFound)
+    );
+    assert!(lineage.kill("bran-stark").is_ok());
+    assert!(lineage.get_from_id("brandon-stark").unwrap().alive());
+}
+
+#[test]
+fn ids_default_to_the_name() {
+    let mut lineage = Lineage::new();
+    // the child is inserted first
+    lineage.insert(ParentChildInfo::new("Jon snow", Male, "Jon Snow", Male));
+    assert_eq!(lineage.get_from_name("Jon Snow").unwrap().id(), "jon-snow");
+    assert_eq!(
+        lineage.get_from_name("Jon snow").unwrap().id(),
+        "jon-snow-2"
+    );
+    assert_eq!(lineage.resolve("jon-snow-2").unwrap().name(), "Jon snow");
+}
+
+#[test]
+fn rejects_ambiguous_or_conflicting_insertions() {
+    let mut lineage = create_lineage();
+    assert_eq!(
+        lineage.try_insert(ParentChildInfo::new(
+            "Brandon Stark",
+            Male,
+            "Rickon Stark",
+            Male
+        )),
+        Err(InsertError::AmbiguousName {
+            name: "Brandon Stark".to_string(),
+            candidates: vec!["brandon-stark".to_string(), "bran-stark".to_string()],
+        })
+    );
+    assert_eq!(
+        lineage.try_insert(
+            ParentChildInfo::new("Eddard Stark", Male, "Rickon Stark", Male)
+                .with_ids("eddard-stark", "bran-stark")
+        ),
+        Err(InsertError::IdConflict {
+            id: "bran-stark".to_string(),
+            name: "Rickon Stark".to_string(),
+            existing_name: "Brandon Stark".to_string(),
+        })
+    );
+    assert_eq!(
+        lineage.add_person("Brandon Stark", Male, None),
+        Err(InsertError::PersonAlreadyExists(
+            "Brandon Stark".to_string()
+        ))
+    );
+    assert!(lineage
+        .add_person("Brandon Stark", Male, Some("brandon-the-builder"))
+        .is_ok());
+}
+
+#[test]
+fn ids_are_optional_columns() {
+    let csv = "parent_name, parent_sex, child_name, child_sex, parent_id, child_id\n\
+               Eddard Stark, M, Brandon Stark, M, , bran-stark\n\
+               Catelyn Tully, F, Brandon Stark, M, , bran-stark\n";
+    let mut rdr = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(csv.as_bytes());
+    let mut lineage = Lineage::new();
+    for info in rdr.deserialize::<ParentChildInfo>() {
+        lineage.insert(info.unwrap());
+    }
+    let bran = lineage.get_from_id("bran-stark").unwrap();
+    assert_eq!(lineage.get_mother_of(bran).unwrap().id(), "catelyn-tully");
+    assert_eq!(lineage.get_father_of(bran).unwrap().id(), "eddard-stark");
+
+    let without_ids = "parent_name, parent_sex, child_name, child_sex\n\
+                       Eddard Stark, M, Robb Stark, M\n";
+    let mut rdr = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(without_ids.as_bytes());
+    let info: ParentChildInfo = rdr.deserialize().next().unwrap().unwrap();
+    assert_eq!(
+        info,
+        ParentChildInfo::new("Eddard Stark", Male, "Robb Stark", Male)
+    );
+}
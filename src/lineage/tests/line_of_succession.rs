@@ -0,0 +1,54 @@
+use super::next_in_line::create_lineage;
+use crate::lineage::{
+    Lineage, ParentChildInfo,
+    Sex::{Female, Male},
+    SuccessionLaw,
+};
+
+fn names(lineage: &Lineage, person: &str, max: Option<usize>) -> Vec<String> {
+    let person = lineage.get_from_name(person).unwrap();
+    lineage
+        .line_of_succession(person, SuccessionLaw::MalePreference, max)
+        .iter()
+        .map(|succession| succession.successor.name().to_string())
+        .collect()
+}
+
+#[test]
+fn starts_with_the_heir() {
+    let lineage = create_lineage();
+    let father = lineage.get_from_name("M L1").unwrap();
+    let heir = lineage
+        .succession_of(father, SuccessionLaw::MalePreference)
+        .unwrap();
+    // sons, then daughters, then the rest of the house
+    assert_eq!(
+        names(&lineage, "M L1", None),
+        vec!["SA L2", "SB L2", "DA L2", "DB L2", "F L1"]
+    );
+    assert_eq!(heir.successor.name(), "SA L2");
+}
+
+#[test]
+fn follows_the_heirs_of_the_dead() {
+    let mut lineage = create_lineage();
+    let line = names(&lineage, "M L1", None);
+    // the second in line inherits once the first dies, and so on
+    lineage.kill(&line[0]).unwrap();
+    assert_eq!(names(&lineage, "M L1", None), line[1..].to_vec());
+}
+
+#[test]
+fn is_cut_at_the_maximum() {
+    let lineage = create_lineage();
+    let line = names(&lineage, "M L1", None);
+    assert_eq!(names(&lineage, "M L1", Some(2)), line[..2].to_vec());
+    assert!(names(&lineage, "M L1", Some(0)).is_empty());
+}
+
+#[test]
+fn is_empty_without_relatives() {
+    let mut lineage = Lineage::new();
+    lineage.insert(ParentChildInfo::new("Mother A", Female, "Son B", Male));
+    assert!(names(&lineage, "Son B", None).is_empty());
+}
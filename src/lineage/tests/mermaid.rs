@@ -0,0 +1,134 @@
+use crate::lineage::{
+    GraphOptions, Lineage, ParentChildInfo, PersonFilter,
+    Sex::{Female, Male},
+};
+
+/// Creates the following lineage
+///
+///   (Father X + Mother Y)
+///     /        \
+///   Son X    Daughter X
+///     |
+///   Grandson X
+fn create_lineage() -> Lineage {
+    let mut lineage = Lineage::new();
+    for (parent, parent_sex, child, child_sex) in &[
+        ("Father X", Male, "Son X", Male),
+        ("Mother Y", Female, "Son X", Male),
+        ("Father X", Male, "Daughter X", Female),
+        ("Mother Y", Female, "Daughter X", Female),
+        ("Son X", Male, "Grandson X", Male),
+    ] {
+        lineage.insert(ParentChildInfo::new(
+            *parent,
+            parent_sex.clone(),
+            *child,
+            child_sex.clone(),
+        ));
+    }
+    lineage
+}
+
+#[test]
+fn flowchart_links_each_parent() {
+    let lineage = create_lineage();
+    let mermaid = lineage.to_mermaid(&GraphOptions::default());
+    assert!(mermaid.starts_with("flowchart TD\n"));
+    assert!(mermaid.contains("    son_x[\"Son X\"]\n"));
+    assert!(mermaid.contains("    mother_y([\"Mother Y\"])\n"));
+    assert!(mermaid.contains("    father_x --> son_x\n"));
+    assert!(mermaid.contains("    mother_y --> daughter_x\n"));
+    assert!(!mermaid.contains("subgraph"));
+    assert!(!mermaid.contains("classDef"));
+}
+
+#[test]
+fn houses_are_subgraphs() {
+    let lineage = create_lineage();
+    let mermaid = lineage.to_mermaid(&GraphOptions {
+        house_clusters: true,
+        ..GraphOptions::default()
+    });
+    assert!(mermaid.contains(
+        "    subgraph house_X[\"House X\"]\n        son_x[\"Son X\"]\n        father_x[\"Father X\"]\n"
+    ));
+    assert!(mermaid.contains(
+        "    subgraph house_Y[\"House Y\"]\n        mother_y([\"Mother Y\"])\n    end\n"
+    ));
+}
+
+#[test]
+fn dead_are_greyed_out_and_heir_highlighted() {
+    let mut lineage = create_lineage();
+    lineage.kill("Son X").unwrap();
+    let father = lineage.get_from_name("Father X").unwrap();
+    let mermaid = lineage.to_mermaid(&GraphOptions {
+        grey_out_dead: true,
+        heir_of: Some(father),
+        ..GraphOptions::default()
+    });
+    assert!(mermaid.contains("    class son_x dead\n"));
+    assert!(mermaid.contains("    class father_x heirOf\n"));
+    // the daughter inherits, through the third link
+    assert!(mermaid.contains("    class daughter_x heir\n"));
+    assert!(mermaid.contains("    linkStyle 2 stroke:#d4a017,stroke-width:3px\n"));
+}
+
+#[test]
+fn filters_the_people() {
+    let mut lineage = create_lineage();
+    lineage.kill("Grandson X").unwrap();
+    let son = lineage.get_from_name("Son X").unwrap();
+    let mermaid = lineage.to_mermaid(&GraphOptions {
+        subtree_of: Some(son),
+        ..GraphOptions::default()
+    });
+    assert!(mermaid.contains("son_x --> grandson_x"));
+    assert!(!mermaid.contains("father_x"));
+    assert!(!mermaid.contains("daughter_x"));
+
+    let mermaid = lineage.to_mermaid(&GraphOptions {
+        filter: PersonFilter {
+            alive: Some(true),
+            house: Some("X".to_string()),
+            ..PersonFilter::default()
+        },
+        ..GraphOptions::default()
+    });
+    assert!(mermaid.contains("father_x --> son_x"));
+    assert!(!mermaid.contains("mother_y"));
+    assert!(!mermaid.contains("grandson_x"));
+}
+
+#[test]
+fn labels_are_escaped() {
+    let mut lineage = Lineage::new();
+    lineage.insert(ParentChildInfo::new(
+        "Walder \"The Late\" Frey",
+        Male,
+        "Black Walder Frey",
+        Male,
+    ));
+    let mermaid = lineage.to_mermaid(&GraphOptions::default());
+    assert!(mermaid.contains("    walder_the_late_frey[\"Walder #quot;The Late#quot; Frey\"]\n"));
+}
+
+#[test]
+fn identifiers_stay_distinct() {
+    let mut lineage = Lineage::new();
+    lineage.insert(
+        ParentChildInfo::new("Parent House", Male, "Child House", Male).with_ids("a-b", "a_b"),
+    );
+    lineage.insert(
+        ParentChildInfo::new("Parent House", Male, "Other House", Male)
+            .with_ids("a-b", "house_House"),
+    );
+    let mermaid = lineage.to_mermaid(&GraphOptions {
+        house_clusters: true,
+        ..GraphOptions::default()
+    });
+    // the child is inserted before its parent
+    assert!(mermaid.contains("    a_b_2 --> a_b\n"));
+    assert!(mermaid.contains("    a_b_2 --> house_House_2\n"));
+    assert!(mermaid.contains("    subgraph house_House[\"House House\"]\n"));
+}
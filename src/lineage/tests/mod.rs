@@ -1,2 +1,15 @@
 mod family_member_api;
+mod generations;
+mod graphviz;
+mod ids;
+mod line_of_succession;
+mod mermaid;
+mod mutations;
+mod names;
 mod next_in_line;
+mod plantuml;
+mod relationship;
+mod siblings_children;
+mod succession_changes;
+mod svg;
+mod text_tree;
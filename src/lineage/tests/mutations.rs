@@ -0,0 +1,216 @@
+use crate::lineage::{
+    InsertError, KillError, Lineage, ParentChildInfo, ReviveError,
+    Sex::{Female, Male},
+    UnlinkError,
+};
+
+fn create_lineage() -> Lineage {
+    let mut lineage = Lineage::new();
+    lineage.insert(ParentChildInfo::new(
+        "Father House",
+        Male,
+        "Son House",
+        Male,
+    ));
+    lineage.insert(ParentChildInfo::new(
+        "Son House",
+        Male,
+        "Grandson House",
+        Male,
+    ));
+    lineage
+}
+
+#[test]
+fn try_insert_rejects_invalid_relationships() {
+    let mut lineage = create_lineage();
+    let people_before = lineage.people().clone();
+    let invalid = vec![
+        (
+            ParentChildInfo::new("Father", Male, "Daughter House", Female),
+            InsertError::MissingHouseName("Father".to_string()),
+        ),
+        (
+            ParentChildInfo::new("Father House", Female, "Daughter House", Female),
+            InsertError::SexMismatch("Father House".to_string()),
+        ),
+        (
+            ParentChildInfo::new("Grandson House", Male, "Father House", Male),
+            InsertError::Cycle {
+                parent: "Grandson House".to_string(),
+                child: "Father House".to_string(),
+            },
+        ),
+        (
+            ParentChildInfo::new("Son House", Male, "Son House", Male),
+            InsertError::Cycle {
+                parent: "Son House".to_string(),
+                child: "Son House".to_string(),
+            },
+        ),
+        (
+            ParentChildInfo::new("Father House", Male, "Son House", Male),
+            InsertError::RelationshipAlreadyExists {
+                parent: "Father House".to_string(),
+                child: "Son House".to_string(),
+            },
+        ),
+        (
+            ParentChildInfo::new("Stranger Other", Male, "Son House", Male),
+            InsertError::ParentAlreadySet {
+                child: "Son House".to_string(),
+                existing_parent: "Father House".to_string(),
+                new_parent: "Stranger Other".to_string(),
+            },
+        ),
+    ];
+    for (parent_child, error) in invalid {
+        assert_eq!(lineage.try_insert(parent_child), Err(error));
+        // nothing was changed
+        assert_eq!(lineage.people(), &people_before);
+    }
+
+    lineage
+        .try_insert(ParentChildInfo::new(
+            "Mother House",
+            Female,
+            "Son House",
+            Male,
+        ))
+        .unwrap();
+    let son = lineage.get_from_name("Son House").unwrap();
+    assert_eq!(lineage.get_mother_of(son).unwrap().name, "Mother House");
+}
+
+#[test]
+fn can_add_person() {
+    let mut lineage = create_lineage();
+    lineage.add_person("Stranger Other", Female, None).unwrap();
+    let added = lineage.get_from_name("Stranger Other").unwrap();
+    assert_eq!(added.name, "Stranger Other");
+    assert!(added.alive());
+    assert_eq!(
+        lineage
+            .add_person("Stranger Other", Female, None)
+            .unwrap_err(),
+        InsertError::PersonAlreadyExists("Stranger Other".to_string())
+    );
+    assert_eq!(
+        lineage.add_person("Stranger", Female, None).unwrap_err(),
+        InsertError::MissingHouseName("Stranger".to_string())
+    );
+    // the added person can then be related to others
+    lineage
+        .try_insert(ParentChildInfo::new(
+            "Stranger Other",
+            Female,
+            "Grandson House",
+            Male,
+        ))
+        .unwrap();
+}
+
+#[test]
+fn can_remove_relationship() {
+    let mut lineage = create_lineage();
+    lineage
+        .remove_relationship("Father House", "Son House")
+        .unwrap();
+    let son = lineage.get_from_name("Son House").unwrap();
+    assert!(lineage.get_father_of(son).is_none());
+    let father = lineage.get_from_name("Father House").unwrap();
+    assert!(lineage.get_sons_of(father).is_empty());
+    // the son is no longer the heir, only a member of the house
+    assert_eq!(
+        lineage.next_in_line("Father House").unwrap().name,
+        "Grandson House"
+    );
+
+    assert_eq!(
+        lineage.remove_relationship("Father House", "Son House"),
+        Err(UnlinkError::NotParentOf {
+            parent: "Father House".to_string(),
+            child: "Son House".to_string()
+        })
+    );
+    assert_eq!(
+        lineage.remove_relationship("Nobody House", "Son House"),
+        Err(UnlinkError::PersonNotFound("Nobody House".to_string()))
+    );
+    // it can be added back
+    lineage.insert(ParentChildInfo::new(
+        "Father House",
+        Male,
+        "Son House",
+        Male,
+    ));
+}
+
+#[test]
+fn batch_kill_is_atomic() {
+    let mut lineage = create_lineage();
+    lineage.insert(ParentChildInfo::new(
+        "Son House",
+        Male,
+        "Daughter House",
+        Female,
+    ));
+    lineage.kill("Son House").unwrap();
+
+    // one unknown name prevents everybody from being killed
+    let outcomes = lineage
+        .kill_batch(&["Father House", "Nobody House", "Son House"])
+        .unwrap_err();
+    assert_eq!(
+        outcomes,
+        vec![
+            Ok(()),
+            Err(KillError::PersonNotFound),
+            Err(KillError::PersonAlreadyDead)
+        ]
+    );
+    assert!(lineage.people().iter().filter(|p| p.alive()).count() == 3);
+
+    // people already dead or repeated do not prevent the others from being killed
+    let (outcomes, _) = lineage
+        .kill_batch(&[
+            "Father House",
+            "Son House",
+            "Grandson House",
+            "Father House",
+        ])
+        .unwrap();
+    assert_eq!(
+        outcomes,
+        vec![
+            Ok(()),
+            Err(KillError::PersonAlreadyDead),
+            Ok(()),
+            Err(KillError::PersonAlreadyDead)
+        ]
+    );
+    let alive: Vec<&str> = lineage
+        .people()
+        .iter()
+        .filter(|p| p.alive())
+        .map(|p| p.name())
+        .collect();
+    assert_eq!(alive, vec!["Daughter House"]);
+}
+
+#[test]
+fn can_revive() {
+    let mut lineage = create_lineage();
+    assert_eq!(lineage.revive("Son House"), Err(ReviveError::PersonAlive));
+    lineage.kill("Son House").unwrap();
+    lineage.revive("Son House").unwrap();
+    assert!(lineage.get_from_name("Son House").unwrap().alive());
+    assert_eq!(
+        lineage.next_in_line("Father House").unwrap().name,
+        "Son House"
+    );
+    assert_eq!(
+        lineage.revive("Nobody House"),
+        Err(ReviveError::PersonNotFound)
+    );
+}
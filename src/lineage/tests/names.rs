@@ -0,0 +1,113 @@
+use crate::lineage::{
+    Lineage, ParentChildInfo, ResolveError,
+    Sex::{Female, Male},
+    UnknownName,
+};
+
+fn create_lineage() -> Lineage {
+    let mut lineage = Lineage::new();
+    lineage.insert(ParentChildInfo::new(
+        "Rickard Stark",
+        Male,
+        "Eddard Stark",
+        Male,
+    ));
+    lineage.insert(ParentChildInfo::new(
+        "Rickard Stark",
+        Male,
+        "Benjen Stark",
+        Male,
+    ));
+    lineage.insert(ParentChildInfo::new(
+        "Tywin Lannister",
+        Male,
+        "Jamie Lannister",
+        Male,
+    ));
+    lineage.insert(ParentChildInfo::new(
+        "Tywin Lannister",
+        Male,
+        "Cersei Lannister",
+        Female,
+    ));
+    lineage.add_alias("Ned", "Eddard Stark").unwrap();
+    lineage.add_alias("Kingslayer", "Jamie Lannister").unwrap();
+    lineage
+}
+
+fn resolved(lineage: &Lineage, name: &str) -> Option<String> {
+    lineage
+        .resolve(name)
+        .ok()
+        .map(|person| person.name().to_string())
+}
+
+fn suggestions(lineage: &Lineage, name: &str) -> Vec<String> {
+    match lineage.resolve(name) {
+        Err(ResolveError::Unknown(unknown)) => unknown.suggestions,
+        resolved => panic!("{} is not unknown: {:?}", name, resolved),
+    }
+}
+
+#[test]
+fn resolves_regardless_of_case_and_whitespace() {
+    let lineage = create_lineage();
+    for name in &[
+        "Eddard Stark",
+        "eddard stark",
+        "EDDARD STARK",
+        " Eddard   Stark ",
+        "Eddard Stark\t",
+    ] {
+        assert_eq!(resolved(&lineage, name), Some("Eddard Stark".to_string()));
+    }
+}
+
+#[test]
+fn resolves_aliases() {
+    let lineage = create_lineage();
+    assert_eq!(resolved(&lineage, "ned"), Some("Eddard Stark".to_string()));
+    assert_eq!(
+        resolved(&lineage, "Ned Stark"),
+        Some("Eddard Stark".to_string())
+    );
+    assert_eq!(
+        resolved(&lineage, "the kingslayer"),
+        None,
+        "aliases are whole names"
+    );
+    assert_eq!(
+        resolved(&lineage, "Kingslayer"),
+        Some("Jamie Lannister".to_string())
+    );
+    // a first name alias only stands for its own house
+    assert_eq!(resolved(&lineage, "Ned Lannister"), None);
+}
+
+#[test]
+fn aliases_need_an_existing_person() {
+    let mut lineage = create_lineage();
+    assert_eq!(
+        lineage.add_alias("Jaime", "Jaime Lannister"),
+        Err(ResolveError::Unknown(UnknownName {
+            name: "Jaime Lannister".to_string(),
+            suggestions: vec!["Jamie Lannister".to_string()],
+        }))
+    );
+    assert!(lineage.resolve("Jaime").is_err());
+}
+
+#[test]
+fn suggests_close_names() {
+    let lineage = create_lineage();
+    assert_eq!(
+        suggestions(&lineage, "Edard Strak"),
+        vec!["Eddard Stark".to_string()]
+    );
+    // aliases suggest the person they stand for
+    assert_eq!(
+        suggestions(&lineage, "Kinslayer"),
+        vec!["Jamie Lannister".to_string()]
+    );
+    assert!(suggestions(&lineage, "Daenerys Targaryen").is_empty());
+}
@@ -1,6 +1,7 @@
 use crate::lineage::{
     Lineage, ParentChildInfo,
     Sex::{Female, Male},
+    SuccessionLaw,
 };
 
 /// Creates the following dummy lineage used in testing
@@ -25,7 +26,7 @@ use crate::lineage::{
 ///      /    \
 ///     /      \
 ///   SF_L2   DF_L2
-fn create_lineage() -> Lineage {
+pub(super) fn create_lineage() -> Lineage {
     let mut lineage = Lineage::new();
 
     lineage.insert(ParentChildInfo::new("F L1", Female, "SA L2", Male));
@@ -150,3 +151,90 @@ fn anyone_alive_from_house_after_niece() {
     lin.kill("DE LE2").unwrap(); // kill second niece
     assert_eq!(lin.next_in_line("SA L2").unwrap().name, "DF L2");
 }
+
+#[test]
+fn absolute_primogeniture_ignores_sex() {
+    let mut lin = create_lineage();
+    let law = SuccessionLaw::AbsolutePrimogeniture;
+    // daughter comes before the son alphabetically
+    assert_eq!(lin.next_in_line_under("SA L2", law).unwrap().name, "DC L2");
+    lin.kill("DC L2").unwrap();
+    assert_eq!(lin.next_in_line_under("SA L2", law).unwrap().name, "SC L2");
+    lin.kill("SC L2").unwrap();
+    // sisters come before the brother alphabetically
+    assert_eq!(lin.next_in_line_under("SA L2", law).unwrap().name, "DA L2");
+    lin.kill("DA L2").unwrap();
+    lin.kill("DB L2").unwrap();
+    lin.kill("SB L2").unwrap();
+    // nieces and nephews share the same tier
+    assert_eq!(lin.next_in_line_under("SA L2", law).unwrap().name, "DD LE2");
+}
+
+#[test]
+fn full_blood_preference_ranks_siblings_by_kind() {
+    let mut lin = Lineage::new();
+    for (parent, parent_sex, child) in &[
+        ("Father K", Male, "Me K"),
+        ("Mother K", Female, "Me K"),
+        ("Father K", Male, "Zed K"),
+        ("Mother K", Female, "Zed K"),
+        ("Father K", Male, "Abel K"),
+        ("Other J", Female, "Abel K"),
+        ("Mother K", Female, "Aaron M"),
+        ("Lover M", Male, "Aaron M"),
+    ] {
+        lin.insert(ParentChildInfo::new(
+            *parent,
+            parent_sex.clone(),
+            *child,
+            Male,
+        ));
+    }
+    // all the brothers share the same tier under male preference
+    assert_eq!(lin.next_in_line("Me K").unwrap().name, "Aaron M");
+    let law = SuccessionLaw::FullBloodPreference;
+    assert_eq!(lin.next_in_line_under("Me K", law).unwrap().name, "Zed K");
+    lin.kill("Zed K").unwrap();
+    assert_eq!(lin.next_in_line_under("Me K", law).unwrap().name, "Abel K");
+    lin.kill("Abel K").unwrap();
+    // the maternal half-brother is not an heir as a brother, the house is next
+    assert_eq!(
+        lin.next_in_line_under("Me K", law).unwrap().name,
+        "Father K"
+    );
+}
+
+#[test]
+fn full_blood_preference_keeps_the_children_of_maternal_half_siblings() {
+    let mut lin = Lineage::new();
+    for (parent, parent_sex, child) in &[
+        ("Father K", Male, "Me K"),
+        ("Mother K", Female, "Me K"),
+        ("Father K", Male, "Abel K"),
+        ("Other J", Female, "Abel K"),
+        ("Mother K", Female, "Aaron M"),
+        ("Lover M", Male, "Aaron M"),
+        ("Aaron M", Male, "Aaron-Son M"),
+        ("Abel K", Male, "Zed-Son K"),
+    ] {
+        lin.insert(ParentChildInfo::new(
+            *parent,
+            parent_sex.clone(),
+            *child,
+            Male,
+        ));
+    }
+    let law = SuccessionLaw::FullBloodPreference;
+    lin.kill("Abel K").unwrap();
+    // the son of the paternal half-brother comes first, despite the alphabetical order
+    assert_eq!(
+        lin.next_in_line_under("Me K", law).unwrap().name,
+        "Zed-Son K"
+    );
+    lin.kill("Zed-Son K").unwrap();
+    // the maternal half-brother is skipped, not his son, who comes before the house
+    assert_eq!(
+        lin.next_in_line_under("Me K", law).unwrap().name,
+        "Aaron-Son M"
+    );
+}
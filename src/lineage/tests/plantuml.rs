@@ -0,0 +1,94 @@
+use crate::lineage::{
+    GraphOptions, Lineage, ParentChildInfo, PersonFilter,
+    Sex::{Female, Male},
+};
+
+/// Creates the following lineage
+///
+///   (Father X + Mother Y)
+///     /        \
+///   Son X    Daughter X
+fn create_lineage() -> Lineage {
+    let mut lineage = Lineage::new();
+    for (parent, parent_sex, child, child_sex) in &[
+        ("Father X", Male, "Son X", Male),
+        ("Mother Y", Female, "Son X", Male),
+        ("Father X", Male, "Daughter X", Female),
+        ("Mother Y", Female, "Daughter X", Female),
+    ] {
+        lineage.insert(ParentChildInfo::new(
+            *parent,
+            parent_sex.clone(),
+            *child,
+            child_sex.clone(),
+        ));
+    }
+    lineage
+}
+
+#[test]
+fn diagram_links_each_parent() {
+    let lineage = create_lineage();
+    let uml = lineage.to_plantuml(&GraphOptions::default());
+    assert!(uml.starts_with("@startuml\n"));
+    assert!(uml.ends_with("@enduml\n"));
+    assert!(uml.contains("rectangle \"Son X\" as son_x\n"));
+    assert!(uml.contains("usecase \"Mother Y\" as mother_y\n"));
+    assert!(uml.contains("father_x --> son_x\n"));
+    assert!(uml.contains("mother_y --> daughter_x\n"));
+    assert!(!uml.contains("package"));
+}
+
+#[test]
+fn houses_are_packages() {
+    let lineage = create_lineage();
+    let uml = lineage.to_plantuml(&GraphOptions {
+        house_clusters: true,
+        ..GraphOptions::default()
+    });
+    assert!(uml.contains("package \"House Y\" {\n  usecase \"Mother Y\" as mother_y\n}\n"));
+}
+
+#[test]
+fn dead_are_greyed_out_and_heir_highlighted() {
+    let mut lineage = create_lineage();
+    lineage.kill("Son X").unwrap();
+    let father = lineage.get_from_name("Father X").unwrap();
+    let uml = lineage.to_plantuml(&GraphOptions {
+        grey_out_dead: true,
+        heir_of: Some(father),
+        ..GraphOptions::default()
+    });
+    assert!(uml.contains("rectangle \"Son X\" as son_x #DDDDDD;line.dashed;text:666666\n"));
+    assert!(uml.contains("rectangle \"Father X\" as father_x #line.bold\n"));
+    assert!(uml.contains("usecase \"Daughter X\" as daughter_x #line:D4A017;line.bold\n"));
+    assert!(uml.contains("father_x -[#D4A017,bold]-> daughter_x\n"));
+    assert!(uml.contains("mother_y --> daughter_x\n"));
+}
+
+#[test]
+fn filters_the_people() {
+    let lineage = create_lineage();
+    let uml = lineage.to_plantuml(&GraphOptions {
+        filter: PersonFilter {
+            sex: Some(Female),
+            ..PersonFilter::default()
+        },
+        ..GraphOptions::default()
+    });
+    assert!(uml.contains("mother_y --> daughter_x\n"));
+    assert!(!uml.contains("son_x"));
+    assert!(!uml.contains("father_x"));
+}
+
+#[test]
+fn identifiers_stay_distinct() {
+    let mut lineage = Lineage::new();
+    lineage.insert(
+        ParentChildInfo::new("Parent House", Male, "Child House", Male).with_ids("a-b", "a_b"),
+    );
+    let uml = lineage.to_plantuml(&GraphOptions::default());
+    assert!(uml.contains("\"Child House\" as a_b\n"));
+    assert!(uml.contains("\"Parent House\" as a_b_2\n"));
+    assert!(uml.contains("a_b_2 --> a_b\n"));
+}
@@ -0,0 +1,170 @@
+use crate::lineage::{
+    Lineage, ParentChildInfo,
+    Sex::{Female, Male},
+    SiblingKind,
+};
+
+/// Creates the following lineage, Half X being the son of Father X and Other W
+///
+///            (Grandfather X + Grandmother X)
+///              /                      \
+///   (Mother Z + Father X + Other W)   (Uncle X + Aunt Y)
+///        /        \          \              |
+///      Me X     Sister X    Half X       Cousin X
+///       |                                   |
+///     Son X                            Cousin-Son X
+fn create_lineage() -> Lineage {
+    let mut lineage = Lineage::new();
+    for (parent, parent_sex, child, child_sex) in &[
+        ("Grandfather X", Male, "Father X", Male),
+        ("Grandmother X", Female, "Father X", Male),
+        ("Grandfather X", Male, "Uncle X", Male),
+        ("Grandmother X", Female, "Uncle X", Male),
+        ("Father X", Male, "Me X", Male),
+        ("Mother Z", Female, "Me X", Male),
+        ("Father X", Male, "Sister X", Female),
+        ("Mother Z", Female, "Sister X", Female),
+        ("Father X", Male, "Half X", Male),
+        ("Other W", Female, "Half X", Male),
+        ("Uncle X", Male, "Cousin X", Female),
+        ("Aunt Y", Female, "Cousin X", Female),
+        ("Cousin X", Female, "Cousin-Son X", Male),
+        ("Me X", Male, "Son X", Male),
+    ] {
+        lineage.insert(ParentChildInfo::new(
+            *parent,
+            parent_sex.clone(),
+            *child,
+            child_sex.clone(),
+        ));
+    }
+    lineage
+}
+
+fn relationship(lineage: &Lineage, a: &str, b: &str) -> Option<String> {
+    let a = lineage.get_from_name(a).unwrap();
+    let b = lineage.get_from_name(b).unwrap();
+    lineage
+        .relationship(a, b)
+        .map(|relationship| relationship.name)
+}
+
+#[test]
+fn names_relationships() {
+    let lineage = create_lineage();
+    for (a, b, expected) in &[
+        ("Me X", "Me X", "self"),
+        ("Me X", "Father X", "father"),
+        ("Me X", "Grandmother X", "grandmother"),
+        ("Grandfather X", "Son X", "great-grandson"),
+        ("Father X", "Me X", "son"),
+        ("Me X", "Sister X", "sister"),
+        ("Me X", "Half X", "half-brother"),
+        ("Sister X", "Son X", "nephew"),
+        ("Me X", "Uncle X", "uncle"),
+        ("Son X", "Uncle X", "great-uncle"),
+        ("Uncle X", "Son X", "great-nephew"),
+        ("Me X", "Cousin X", "first cousin"),
+        ("Me X", "Cousin-Son X", "first cousin once removed"),
+        ("Cousin-Son X", "Me X", "first cousin once removed"),
+        ("Son X", "Cousin-Son X", "second cousin"),
+    ] {
+        assert_eq!(
+            relationship(&lineage, a, b).as_deref(),
+            Some(*expected),
+            "{} to {}",
+            a,
+            b
+        );
+    }
+}
+
+#[test]
+fn in_laws_are_not_blood_relatives() {
+    let lineage = create_lineage();
+    assert_eq!(relationship(&lineage, "Me X", "Aunt Y"), None);
+    assert_eq!(relationship(&lineage, "Mother Z", "Other W"), None);
+}
+
+#[test]
+fn goes_through_the_lowest_common_ancestors() {
+    let lineage = create_lineage();
+    let me = lineage.get_from_name("Me X").unwrap();
+    let cousin = lineage.get_from_name("Cousin X").unwrap();
+    let relationship = lineage.relationship(me, cousin).unwrap();
+    assert_eq!(relationship.generations_up, 2);
+    assert_eq!(relationship.generations_down, 2);
+    assert!(!relationship.half);
+    let names = |people: &[&crate::lineage::Person]| {
+        people
+            .iter()
+            .map(|person| person.name().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        names(&relationship.common_ancestors),
+        vec!["Grandfather X", "Grandmother X"]
+    );
+    assert_eq!(
+        names(&relationship.path),
+        vec!["Me X", "Father X", "Grandfather X", "Uncle X", "Cousin X"]
+    );
+}
+
+#[test]
+fn ties_between_ancestors_are_broken_the_same_way() {
+    // the father of A is a great-grandfather of B, and the father of B a great-grandfather of A
+    let mut lineage = Lineage::new();
+    for (parent, parent_sex, child, child_sex) in &[
+        ("P House", Male, "A House", Male),
+        ("X House", Female, "A House", Male),
+        ("Y House", Female, "X House", Female),
+        ("Q House", Male, "Y House", Female),
+        ("P House", Male, "R House", Male),
+        ("R House", Male, "S House", Female),
+        ("Q House", Male, "B House", Female),
+        ("S House", Female, "B House", Female),
+    ] {
+        lineage.insert(ParentChildInfo::new(
+            *parent,
+            parent_sex.clone(),
+            *child,
+            child_sex.clone(),
+        ));
+    }
+    let a = lineage.get_from_name("A House").unwrap();
+    let b = lineage.get_from_name("B House").unwrap();
+    // the ancestries are hash maps, seeded differently each time
+    for _ in 0..20 {
+        let relationship = lineage.relationship(a, b).unwrap();
+        assert_eq!(
+            (relationship.generations_up, relationship.generations_down),
+            (1, 3)
+        );
+        assert_eq!(relationship.common_ancestors[0].name(), "P House");
+    }
+}
+
+#[test]
+fn a_missing_parent_makes_half_siblings() {
+    let mut lineage = Lineage::new();
+    for (parent, parent_sex, child, child_sex) in &[
+        ("Eddard Stark", Male, "Robb Stark", Male),
+        ("Catelyn Tully", Female, "Robb Stark", Male),
+        ("Eddard Stark", Male, "Jon Snow", Male),
+    ] {
+        lineage.insert(ParentChildInfo::new(
+            *parent,
+            parent_sex.clone(),
+            *child,
+            child_sex.clone(),
+        ));
+    }
+    let robb = lineage.get_from_name("Robb Stark").unwrap();
+    let jon = lineage.get_from_name("Jon Snow").unwrap();
+    let relationship = lineage.relationship(robb, jon).unwrap();
+    assert_eq!(relationship.name, "half-brother");
+    assert!(relationship.half);
+    // as the succession ranks him
+    assert_eq!(lineage.sibling_kind(robb, jon), Some(SiblingKind::Paternal));
+}
@@ -0,0 +1,220 @@
+use crate::lineage::{
+    Lineage, ParentChildInfo, Person,
+    Sex::{Female, Male},
+    SuccessionLaw,
+};
+use proptest::prelude::*;
+use std::collections::HashSet;
+
+const LAWS: [SuccessionLaw; 3] = [
+    SuccessionLaw::MalePreference,
+    SuccessionLaw::AbsolutePrimogeniture,
+    SuccessionLaw::FullBloodPreference,
+];
+
+fn names(people: Vec<&Person>) -> Vec<&str> {
+    people.into_iter().map(|person| person.name()).collect()
+}
+
+/// Creates the following lineage, Zed X and Child-Of-Both X being children of two siblings
+///
+///              (Father X + Mother X)
+///            /        |          \
+///      Me X      Brother X  +   Sister X        Younger X
+///                 /       \       /              (Father X only)
+///         Zed X      Child-Of-Both X                 |
+///                                               Abel X
+fn create_lineage() -> Lineage {
+    let mut lineage = Lineage::new();
+    for (parent, parent_sex, child, child_sex) in &[
+        ("Father X", Male, "Me X", Male),
+        ("Mother X", Female, "Me X", Male),
+        ("Father X", Male, "Brother X", Male),
+        ("Mother X", Female, "Brother X", Male),
+        ("Father X", Male, "Sister X", Female),
+        ("Mother X", Female, "Sister X", Female),
+        ("Father X", Male, "Younger X", Male),
+        ("Brother X", Male, "Zed X", Male),
+        ("Brother X", Male, "Child-Of-Both X", Male),
+        ("Sister X", Female, "Child-Of-Both X", Male),
+        ("Younger X", Male, "Abel X", Male),
+    ] {
+        lineage.insert(ParentChildInfo::new(
+            *parent,
+            parent_sex.clone(),
+            *child,
+            child_sex.clone(),
+        ));
+    }
+    lineage
+}
+
+#[test]
+fn child_of_two_siblings_is_a_single_nephew() {
+    let lineage = create_lineage();
+    let me = lineage.get_from_name("Me X").unwrap();
+    let mut nephews = names(lineage.get_nephews(me));
+    nephews.sort();
+    assert_eq!(nephews, vec!["Abel X", "Child-Of-Both X", "Zed X"]);
+}
+
+#[test]
+fn nephews_ranked_by_the_seniority_of_their_parent() {
+    let mut lineage = create_lineage();
+    let me = lineage.get_from_name("Me X").unwrap();
+    // Abel X comes first alphabetically, but descends from the youngest brother
+    assert_eq!(
+        names(lineage.get_siblings_children_ranked(me, SuccessionLaw::MalePreference, Some(Male))),
+        vec!["Child-Of-Both X", "Zed X", "Abel X"]
+    );
+    // the paternal half-brother comes after the full brother anyway
+    assert_eq!(
+        names(lineage.get_siblings_ranked(me, SuccessionLaw::FullBloodPreference)),
+        vec!["Brother X", "Younger X", "Sister X"]
+    );
+    lineage.kill("Brother X").unwrap();
+    lineage.kill("Younger X").unwrap();
+    // the sons of a dead elder brother inherit before the sons of a younger one
+    assert_eq!(
+        lineage.next_in_line("Me X").unwrap().name(),
+        "Child-Of-Both X"
+    );
+    lineage.kill("Child-Of-Both X").unwrap();
+    assert_eq!(lineage.next_in_line("Me X").unwrap().name(), "Zed X");
+    lineage.kill("Zed X").unwrap();
+    assert_eq!(lineage.next_in_line("Me X").unwrap().name(), "Abel X");
+}
+
+/// Sex of a person and the picks of its father and mother among the people before it, so the
+/// generated lineages have no cycles
+type PersonSpec = (bool, Option<usize>, Option<usize>);
+
+fn build_lineage(specs: &[PersonSpec]) -> Lineage {
+    let mut lineage = Lineage::new();
+    let mut males: Vec<String> = vec![];
+    let mut females: Vec<String> = vec![];
+    for (i, (male, father, mother)) in specs.iter().enumerate() {
+        // few houses, so the house tier is exercised too
+        let name = format!("P{} H{}", i, i % 3);
+        let sex = if *male { Male } else { Female };
+        lineage.add_person(&name, sex.clone(), None).unwrap();
+        let parents = [
+            (
+                father.and_then(|pick| males.get(pick % males.len().max(1))),
+                Male,
+            ),
+            (
+                mother.and_then(|pick| females.get(pick % females.len().max(1))),
+                Female,
+            ),
+        ];
+        for (parent, parent_sex) in parents.iter() {
+            if let Some(parent) = parent {
+                lineage.insert(ParentChildInfo::new(
+                    parent.as_str(),
+                    parent_sex.clone(),
+                    name.as_str(),
+                    sex.clone(),
+                ));
+            }
+        }
+        if *male {
+            males.push(name);
+        } else {
+            females.push(name);
+        }
+    }
+    lineage
+}
+
+fn person_specs() -> impl Strategy<Value = Vec<PersonSpec>> {
+    prop::collection::vec(
+        (
+            any::<bool>(),
+            prop::option::weighted(0.8, 0..8usize),
+            prop::option::weighted(0.8, 0..8usize),
+        ),
+        1..30,
+    )
+}
+
+fn is_unique(people: &[&Person]) -> bool {
+    let idxs: HashSet<usize> = people.iter().map(|person| person.idx()).collect();
+    idxs.len() == people.len()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn siblings_children_have_no_duplicates(specs in person_specs()) {
+        let lineage = build_lineage(&specs);
+        for person in lineage.people() {
+            let nephews = lineage.get_nephews(person);
+            let nieces = lineage.get_nieces(person);
+            prop_assert!(is_unique(&nephews));
+            prop_assert!(is_unique(&nieces));
+            for law in LAWS.iter() {
+                for (sex, expected) in [(Some(Male), &nephews), (Some(Female), &nieces)].iter() {
+                    let ranked = lineage.get_siblings_children_ranked(person, *law, sex.clone());
+                    prop_assert!(is_unique(&ranked));
+                    let ranked: HashSet<&Person> = ranked.into_iter().collect();
+                    let expected: HashSet<&Person> = expected.iter().cloned().collect();
+                    prop_assert_eq!(ranked, expected);
+                }
+                let all = lineage.get_siblings_children_ranked(person, *law, None);
+                prop_assert!(is_unique(&all));
+                prop_assert_eq!(all.len(), nephews.len() + nieces.len());
+            }
+        }
+    }
+
+    #[test]
+    fn siblings_children_follow_the_seniority_of_their_parent(specs in person_specs()) {
+        let lineage = build_lineage(&specs);
+        for person in lineage.people() {
+            for law in LAWS.iter() {
+                let siblings = lineage.get_siblings_ranked(person, *law);
+                prop_assert!(is_unique(&siblings));
+                // rank of the most senior sibling each child descends from
+                let seniority = |child: &Person| {
+                    siblings
+                        .iter()
+                        .position(|sibling| {
+                            child.father_idx() == Some(sibling.idx())
+                                || child.mother_idx() == Some(sibling.idx())
+                        })
+                        .expect("children of siblings descend from one of them")
+                };
+                let ranked = lineage.get_siblings_children_ranked(person, *law, None);
+                for pair in ranked.windows(2) {
+                    let (first, second) = (seniority(pair[0]), seniority(pair[1]));
+                    prop_assert!(
+                        first < second || (first == second && pair[0].name() < pair[1].name())
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn nobody_is_its_own_nephew() {
+    // P4 H1 is the son of P2 H2, its maternal half-brother
+    let lineage = build_lineage(&[
+        (true, None, None),
+        (false, None, None),
+        (true, Some(0), Some(0)),
+        (false, Some(0), Some(0)),
+        (true, Some(1), Some(0)),
+    ]);
+    let child = lineage.get_from_name("P4 H1").unwrap();
+    assert_eq!(lineage.get_father_of(child).unwrap().name(), "P2 H2");
+    assert_eq!(lineage.get_mother_of(child).unwrap().name(), "P1 H1");
+    assert!(lineage.get_nephews(child).is_empty());
+    for law in LAWS.iter() {
+        assert!(lineage
+            .get_siblings_children_ranked(child, *law, None)
+            .is_empty());
+    }
+}
@@ -0,0 +1,191 @@
+use super::next_in_line::create_lineage;
+use crate::lineage::{
+    Lineage, ParentChildInfo,
+    Sex::{Female, Male},
+    SuccessionChange, SuccessionChanges, SuccessionLaw,
+};
+
+fn change(person: &str, old: Option<&str>, new: Option<&str>) -> SuccessionChange {
+    SuccessionChange {
+        person: person.to_string(),
+        old_successor: old.map(str::to_string),
+        new_successor: new.map(str::to_string),
+    }
+}
+
+/// Index of the heir of every person under `law`, in the same order as `people`
+fn successions(lineage: &Lineage, law: SuccessionLaw) -> Vec<Option<usize>> {
+    lineage
+        .people_graph
+        .iter()
+        .map(|person| {
+            lineage
+                .succession_of(person, law)
+                .map(|succession| succession.successor.idx)
+        })
+        .collect()
+}
+
+/// The living people whose heir is not the same as in the `before` snapshot of `successions`,
+/// recomputing everybody, ordered by name. People added since the snapshot had no heir before.
+fn succession_changes(
+    lineage: &Lineage,
+    before: &[Option<usize>],
+    law: SuccessionLaw,
+) -> SuccessionChanges {
+    let name_of = |idx: Option<usize>| idx.map(|idx| lineage.people_graph[idx].name.clone());
+    let mut changes: SuccessionChanges = successions(lineage, law)
+        .into_iter()
+        .enumerate()
+        .filter(|(idx, _)| lineage.people_graph[*idx].alive)
+        .filter_map(|(idx, after)| {
+            let before = before.get(idx).cloned().flatten();
+            if before == after {
+                return None;
+            }
+            Some(SuccessionChange {
+                person: lineage.people_graph[idx].name.clone(),
+                old_successor: name_of(before),
+                new_successor: name_of(after),
+            })
+        })
+        .collect();
+    changes.sort_by(|a, b| a.person.cmp(&b.person));
+    changes
+}
+
+/// Applies the mutation and checks the changes it returns are the ones found recomputing the
+/// heir of everybody
+fn assert_same_as_full_recomputation(
+    lineage: &mut Lineage,
+    mutation: impl FnOnce(&mut Lineage) -> SuccessionChanges,
+) {
+    let law = lineage.succession_law();
+    let before = successions(lineage, law);
+    let changes = mutation(lineage);
+    assert_eq!(changes, succession_changes(lineage, &before, law));
+}
+
+#[test]
+fn kill_returns_whose_heir_changed() {
+    let mut lineage = create_lineage();
+    assert_eq!(
+        lineage.kill("SC L2").unwrap(),
+        vec![
+            change("DC L2", Some("SC L2"), Some("SF L2")),
+            change("EF LE1", Some("SC L2"), Some("DC L2")),
+            change("SA L2", Some("SC L2"), Some("SB L2")),
+        ]
+    );
+    // nobody had the second sister as heir
+    assert_eq!(lineage.kill("DB L2").unwrap(), vec![]);
+}
+
+#[test]
+fn add_person_can_become_heir_of_its_house() {
+    let mut lineage = Lineage::new();
+    lineage.add_person("Robb Stark", Male, None).unwrap();
+    assert_eq!(
+        lineage.add_person("Sansa Stark", Female, None).unwrap(),
+        vec![
+            change("Robb Stark", None, Some("Sansa Stark")),
+            change("Sansa Stark", None, Some("Robb Stark")),
+        ]
+    );
+    // comes first alphabetically, so is the heir of both
+    assert_eq!(
+        lineage.add_person("Arya Stark", Female, None).unwrap(),
+        vec![
+            change("Arya Stark", None, Some("Robb Stark")),
+            change("Robb Stark", Some("Sansa Stark"), Some("Arya Stark")),
+            change("Sansa Stark", Some("Robb Stark"), Some("Arya Stark")),
+        ]
+    );
+    assert_eq!(lineage.add_person("Jon Snow", Male, None).unwrap(), vec![]);
+}
+
+#[test]
+fn mutations_return_the_same_changes_as_a_full_recomputation() {
+    for law in &[
+        SuccessionLaw::MalePreference,
+        SuccessionLaw::AbsolutePrimogeniture,
+        SuccessionLaw::FullBloodPreference,
+    ] {
+        let mut lineage = create_lineage();
+        lineage.set_succession_law(*law);
+        assert_same_as_full_recomputation(&mut lineage, |lineage| {
+            lineage.add_person("AA L2", Male, None).unwrap()
+        });
+        assert_same_as_full_recomputation(&mut lineage, |lineage| {
+            lineage
+                .try_insert(ParentChildInfo::new("SB L2", Male, "AB L2", Female))
+                .unwrap()
+        });
+        assert_same_as_full_recomputation(&mut lineage, |lineage| {
+            lineage
+                .try_insert(ParentChildInfo::new("AA L2", Male, "DE LE2", Female))
+                .unwrap_err();
+            lineage
+                .try_insert(ParentChildInfo::new("AA L2", Male, "SC L2", Male))
+                .unwrap_err();
+            vec![]
+        });
+        assert_same_as_full_recomputation(&mut lineage, |lineage| {
+            lineage.remove_relationship("SA L2", "SC L2").unwrap()
+        });
+        assert_same_as_full_recomputation(&mut lineage, |lineage| {
+            lineage
+                .try_insert(ParentChildInfo::new("SA L2", Male, "SC L2", Male))
+                .unwrap()
+        });
+        assert_same_as_full_recomputation(&mut lineage, |lineage| {
+            lineage.kill_batch(&["SB L2", "M L1"]).unwrap().1
+        });
+        // then everybody else, one by one, until no heir is left
+        let mut alive: Vec<String> = lineage
+            .people()
+            .iter()
+            .filter(|person| person.alive())
+            .map(|person| person.name().to_string())
+            .collect();
+        alive.sort();
+        for name in alive {
+            assert_same_as_full_recomputation(&mut lineage, |lineage| lineage.kill(&name).unwrap());
+        }
+    }
+}
+
+#[test]
+fn namesakes_of_a_house_depend_on_each_other() {
+    // members of the house tier are sorted by name, namesakes in the order they were added
+    let mut lineage = Lineage::new();
+    lineage
+        .add_person("Aegon House", Male, Some("aegon-1"))
+        .unwrap();
+    lineage
+        .add_person("Aegon House", Male, Some("aegon-2"))
+        .unwrap();
+    lineage.add_person("Zed House", Male, None).unwrap();
+    assert_eq!(
+        lineage.kill("aegon-1").unwrap(),
+        vec![
+            change("Aegon House", Some("Aegon House"), Some("Zed House")),
+            change("Zed House", Some("Aegon House"), Some("Aegon House")),
+        ]
+    );
+    assert_same_as_full_recomputation(&mut lineage, |lineage| lineage.revive("aegon-1").unwrap());
+    assert_same_as_full_recomputation(&mut lineage, |lineage| lineage.kill("aegon-2").unwrap());
+    assert_same_as_full_recomputation(&mut lineage, |lineage| {
+        lineage
+            .add_person("Aegon House", Male, Some("aegon-3"))
+            .unwrap()
+    });
+    assert_same_as_full_recomputation(&mut lineage, |lineage| {
+        lineage
+            .try_insert(
+                ParentChildInfo::new("Aegon House", Male, "Aegon House", Male)
+                    .with_ids("aegon-3", "aegon-4"),
+            )
+            .unwrap()
+    });
+}
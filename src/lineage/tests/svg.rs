@@ -0,0 +1,199 @@
+use crate::lineage::{
+    GraphOptions, Lineage, ParentChildInfo,
+    Sex::{Female, Male},
+};
+
+/// Creates the following lineage
+///
+///   (Father X + Mother Y)
+///     /        \
+///   Son X    (Daughter X + Husband Z)
+///                   |
+///               Grandson Z
+fn create_lineage() -> Lineage {
+    let mut lineage = Lineage::new();
+    for (parent, parent_sex, child, child_sex) in &[
+        ("Father X", Male, "Son X", Male),
+        ("Mother Y", Female, "Son X", Male),
+        ("Father X", Male, "Daughter X", Female),
+        ("Mother Y", Female, "Daughter X", Female),
+        ("Husband Z", Male, "Grandson Z", Male),
+        ("Daughter X", Female, "Grandson Z", Male),
+    ] {
+        lineage.insert(ParentChildInfo::new(
+            *parent,
+            parent_sex.clone(),
+            *child,
+            child_sex.clone(),
+        ));
+    }
+    lineage
+}
+
+/// x, y and width of the box of a person
+fn position(svg: &str, id: &str) -> (f64, f64, f64) {
+    let start = svg
+        .find(&format!(r#"id="{}""#, id))
+        .unwrap_or_else(|| panic!("{} is not drawn", id));
+    let rect = &svg[start + svg[start..].find("<rect ").unwrap()..];
+    let attribute = |name: &str| -> f64 {
+        let value = &rect[rect.find(&format!(r#" {}=""#, name)).unwrap() + name.len() + 3..];
+        value[..value.find('"').unwrap()].parse().unwrap()
+    };
+    (attribute("x"), attribute("y"), attribute("width"))
+}
+
+/// The opening tag of the box of a person
+fn rect<'a>(svg: &'a str, id: &str) -> &'a str {
+    let start = svg.find(&format!(r#"id="{}""#, id)).unwrap();
+    let rect = &svg[start + svg[start..].find("<rect ").unwrap()..];
+    &rect[..rect.find("/>").unwrap()]
+}
+
+#[test]
+fn generations_are_rows() {
+    let lineage = create_lineage();
+    let svg = lineage.to_svg(&GraphOptions::default());
+    assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg""#));
+    assert!(svg.ends_with("</svg>\n"));
+    let (_, father, _) = position(&svg, "father-x");
+    let (_, mother, _) = position(&svg, "mother-y");
+    let (_, son, _) = position(&svg, "son-x");
+    let (_, daughter, _) = position(&svg, "daughter-x");
+    let (_, grandson, _) = position(&svg, "grandson-z");
+    assert_eq!(father, mother);
+    assert!(father < son);
+    assert_eq!(son, daughter);
+    assert!(daughter < grandson);
+}
+
+#[test]
+fn spouses_are_side_by_side_on_the_same_row() {
+    let lineage = create_lineage();
+    let svg = lineage.to_svg(&GraphOptions::default());
+    // the husband has no parents but is drawn next to his wife rather than at the top
+    let (daughter_x, daughter_y, daughter_width) = position(&svg, "daughter-x");
+    let (husband_x, husband_y, husband_width) = position(&svg, "husband-z");
+    let (son_x, _, son_width) = position(&svg, "son-x");
+    assert_eq!(daughter_y, husband_y);
+    let (left, left_width, right) = if daughter_x < husband_x {
+        (daughter_x, daughter_width, husband_x)
+    } else {
+        (husband_x, husband_width, daughter_x)
+    };
+    // nobody between them and no overlap in the row
+    assert!(left + left_width < right);
+    assert!(son_x + son_width <= left || son_x >= right);
+    assert_eq!(svg.matches(r#"class="couple""#).count(), 2);
+    // the grandson is under his parents
+    let (grandson_x, _, grandson_width) = position(&svg, "grandson-z");
+    let grandson_center = grandson_x + grandson_width / 2.0;
+    assert!(left < grandson_center && grandson_center < right + husband_width.max(daughter_width));
+}
+
+#[test]
+fn people_married_several_times_are_drawn_together() {
+    // the first husband also married another wife, the wife also another husband
+    let mut lineage = Lineage::new();
+    for (parent, parent_sex, child) in &[
+        ("First X", Male, "Child A"),
+        ("Wife Y", Female, "Child A"),
+        ("First X", Male, "Child B"),
+        ("Other W", Female, "Child B"),
+        ("Second Z", Male, "Child C"),
+        ("Wife Y", Female, "Child C"),
+    ] {
+        lineage.insert(ParentChildInfo::new(
+            *parent,
+            parent_sex.clone(),
+            *child,
+            Male,
+        ));
+    }
+    let svg = lineage.to_svg(&GraphOptions::default());
+    let mut row: Vec<(f64, f64, f64)> = ["first-x", "wife-y", "other-w", "second-z"]
+        .iter()
+        .map(|id| position(&svg, id))
+        .collect();
+    row.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    // a single group of spouses, as far from each other as a couple
+    let gaps: Vec<f64> = row
+        .windows(2)
+        .map(|pair| pair[1].0 - (pair[0].0 + pair[0].2))
+        .collect();
+    assert!(row.iter().all(|(_, y, _)| *y == row[0].1));
+    assert!(gaps.iter().all(|gap| *gap == gaps[0]), "{:?}", gaps);
+}
+
+#[test]
+fn dead_are_greyed_out_and_heir_highlighted() {
+    let mut lineage = create_lineage();
+    lineage.kill("Son X").unwrap();
+    let father = lineage.get_from_name("Father X").unwrap();
+    let svg = lineage.to_svg(&GraphOptions {
+        grey_out_dead: true,
+        heir_of: Some(father),
+        ..GraphOptions::default()
+    });
+    assert!(rect(&svg, "son-x").contains(r##"fill="#ddd" stroke="#333" stroke-dasharray="4 3""##));
+    assert!(rect(&svg, "father-x").contains(r#"stroke-width="3""#));
+    assert!(rect(&svg, "daughter-x").contains(r##"stroke="#d4a017" stroke-width="3""##));
+    assert!(!rect(&svg, "mother-y").contains("stroke-width"));
+    // women have rounded corners
+    assert!(rect(&svg, "daughter-x").contains(r#"rx="18""#));
+    assert!(rect(&svg, "son-x").contains(r#"rx="0""#));
+    // the link from the father to his heir is highlighted
+    let highlighted = svg
+        .lines()
+        .filter(|line| line.contains("<path d=") && line.contains("#d4a017"))
+        .count();
+    assert_eq!(highlighted, 1);
+    assert!(!lineage.to_svg(&GraphOptions::default()).contains("#ddd"));
+}
+
+#[test]
+fn houses_have_their_own_color() {
+    let lineage = create_lineage();
+    let svg = lineage.to_svg(&GraphOptions {
+        house_clusters: true,
+        ..GraphOptions::default()
+    });
+    let fill = |id: &str| {
+        let rect = rect(&svg, id);
+        let fill = &rect[rect.find("fill=").unwrap()..];
+        fill[..fill.find(" stroke").unwrap()].to_string()
+    };
+    assert!(fill("son-x").starts_with(r#"fill="hsl("#));
+    assert_eq!(fill("son-x"), fill("father-x"));
+    assert_ne!(fill("son-x"), fill("mother-y"));
+}
+
+#[test]
+fn draws_only_the_given_people_and_escapes_names() {
+    let mut lineage = create_lineage();
+    lineage.insert(ParentChildInfo::new("Son X", Male, "Tom & Jerry X", Male));
+    let son = lineage.get_from_name("Son X").unwrap();
+    let only = lineage
+        .neighbourhood(son, 0, 1, false)
+        .into_iter()
+        .map(|person| person.idx())
+        .collect();
+    let svg = lineage.to_svg(&GraphOptions {
+        only: Some(only),
+        ..GraphOptions::default()
+    });
+    assert!(svg.contains("<title>Tom &amp; Jerry X</title>"));
+    assert!(!svg.contains("Tom & Jerry"));
+    assert!(!svg.contains("father-x"));
+    let (_, son_y, _) = position(&svg, "son-x");
+    assert_eq!(son_y, 20.0);
+    // a single parent is linked from under its box
+    assert_eq!(svg.matches("<path d=").count(), 1);
+}
+
+#[test]
+fn empty_lineage_is_an_empty_drawing() {
+    let svg = Lineage::new().to_svg(&GraphOptions::default());
+    assert!(svg.contains(r#"width="40" height="40""#));
+    assert!(!svg.contains("<rect"));
+}
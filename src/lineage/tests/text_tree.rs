@@ -0,0 +1,114 @@
+use crate::lineage::{
+    Lineage, ParentChildInfo,
+    Sex::{Female, Male},
+};
+
+/// Creates the following lineage, the grandchildren being cousins
+///
+///            Father X
+///          /         \
+///      Son X       Daughter X
+///     /    \            |
+/// Grandson X  Granddaughter X   Grandson Z
+fn create_lineage() -> Lineage {
+    let mut lineage = Lineage::new();
+    for (parent, parent_sex, child, child_sex) in &[
+        ("Father X", Male, "Son X", Male),
+        ("Father X", Male, "Daughter X", Female),
+        ("Son X", Male, "Grandson X", Male),
+        ("Son X", Male, "Granddaughter X", Female),
+        ("Daughter X", Female, "Grandson Z", Male),
+    ] {
+        lineage.insert(ParentChildInfo::new(
+            *parent,
+            parent_sex.clone(),
+            *child,
+            child_sex.clone(),
+        ));
+    }
+    lineage
+}
+
+#[test]
+fn descendants_are_drawn_as_a_tree() {
+    let mut lineage = create_lineage();
+    lineage.kill("Son X").unwrap();
+    let father = lineage.get_from_name("Father X").unwrap();
+    assert_eq!(
+        lineage.to_text_tree(father, None),
+        "Father X
+├── Son X †
+│   ├── Grandson X
+│   └── Granddaughter X
+└── Daughter X ← heir
+    └── Grandson Z
+"
+    );
+}
+
+#[test]
+fn depth_is_limited() {
+    let lineage = create_lineage();
+    let father = lineage.get_from_name("Father X").unwrap();
+    assert_eq!(
+        lineage.to_text_tree(father, Some(1)),
+        "Father X
+├── Son X ← heir
+└── Daughter X
+"
+    );
+    let grandson = lineage.get_from_name("Grandson X").unwrap();
+    assert_eq!(lineage.to_text_tree(grandson, None), "Grandson X\n");
+}
+
+#[test]
+fn descendants_through_both_parents_are_expanded_once() {
+    let mut lineage = create_lineage();
+    lineage.insert(ParentChildInfo::new(
+        "Grandson X",
+        Male,
+        "Great Grandson X",
+        Male,
+    ));
+    lineage.insert(ParentChildInfo::new(
+        "Granddaughter X",
+        Female,
+        "Great Grandson X",
+        Male,
+    ));
+    lineage.insert(ParentChildInfo::new(
+        "Great Grandson X",
+        Male,
+        "Child X",
+        Male,
+    ));
+    let son = lineage.get_from_name("Son X").unwrap();
+    let tree = lineage.to_text_tree(son, None);
+    assert_eq!(
+        tree,
+        "Son X
+├── Grandson X ← heir
+│   └── Great Grandson X
+│       └── Child X
+└── Granddaughter X
+    └── Great Grandson X (see above)
+"
+    );
+}
+
+#[test]
+fn deep_lineages_do_not_overflow_the_stack() {
+    let mut lineage = Lineage::new();
+    for generation in 0..5_000 {
+        lineage.insert(ParentChildInfo::new(
+            &format!("Person{} X", generation),
+            Male,
+            &format!("Person{} X", generation + 1),
+            Male,
+        ));
+    }
+    let root = lineage.get_from_name("Person0 X").unwrap();
+    let tree = lineage.to_text_tree(root, None);
+    assert_eq!(tree.lines().count(), 5_001);
+    assert!(tree.ends_with("└── Person5000 X\n"));
+}
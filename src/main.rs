@@ -1,115 +1,96 @@
-mod lineage;
-use lineage::{Lineage, ParentChildInfo};
-
-use crate::lineage::KillError;
 use async_std::sync::{Arc, RwLock};
-use serde::Deserialize;
-use std::convert::Infallible;
-use warp::{Filter, Reply};
-
-/// Deserializes a CSV file into a Lineage struct.
-///
-/// # Arguments
-///
-/// * `file_path` - A string slice that holds the csv file path
-///
-/// The file should have the following structure
-/// ```
-///     parent_name, parent_sex, child_name, child_sex
-///     Rickard Stark, M, Eddard Stark, M
-///     Rickard Stark, M, Brandon Stark, M
-/// ```
-fn read_lineage_from_file(file_path: &str) -> Lineage {
-    let file =
-        std::fs::File::open(file_path).unwrap_or_else(|_| panic!("Could open file: {}", file_path));
-    let mut rdr = csv::ReaderBuilder::new()
-        .trim(csv::Trim::All) // trim leading and trailing whitespace from fields
-        .from_reader(file);
-
-    let mut lineage = Lineage::new();
+use craft_ai_project::config::{Command, Config};
+use craft_ai_project::lineage::Lineage;
+use craft_ai_project::persistence::Journal;
+use craft_ai_project::{api, cli, dataset, persistence, repl};
+use std::io::Write;
+use warp::Filter;
 
-    for deserialization_result in rdr.deserialize() {
-        // needs to hint the type here so Serde knows what to try to deserialize to
-        let person_csv: ParentChildInfo = deserialization_result.expect("Invalid CSV entry");
-        lineage.insert(person_csv);
+/// Prints the output of a command, or its error with a failure exit code
+fn exit_with(result: Result<String, String>) -> ! {
+    match result {
+        Ok(output) => {
+            // a closed pipe, as with `| head`, is not a failure of the command
+            let _ = writeln!(std::io::stdout(), "{}", output.trim_end());
+            std::process::exit(0)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1)
+        }
     }
-    lineage
 }
 
-/// Represents the name query parameter in the request
-#[derive(Deserialize)]
-struct NameQueryParam {
-    name: String,
+/// The lineage of the datasets with the aliases and the law of the config
+fn load_lineage(config: &Config) -> Result<Lineage, String> {
+    let mut lineage = dataset::read_lineage(&config.datasets)?;
+    lineage.set_succession_law(config.succession_law);
+    for aliases_path in &config.aliases {
+        dataset::add_aliases(aliases_path, &mut lineage)?;
+    }
+    Ok(lineage)
 }
 
-/// GET /successor/{name} => 200 OK with body "{name} successor"
-pub fn get_successor_route(
-    lineage_ref: Arc<RwLock<Lineage>>,
-) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    warp::get() // only get requests
-        .and(warp::path!("successor")) // only matching successor path
-        .and(warp::query::<NameQueryParam>()) // only having a name query parameter
-        .and(warp::any().map(move || lineage_ref.clone()))
-        .and_then(get_successor)
-}
+fn main() {
+    let config = Config::load().unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1)
+    });
 
-/// This cant be inline because async closures are unstable for now, needs to be a standalone function
-async fn get_successor(
-    query: NameQueryParam,
-    lineage: Arc<RwLock<Lineage>>,
-) -> Result<impl warp::Reply, Infallible> {
-    let maybe_successor = lineage.read().await.next_in_line(&query.name);
-    Ok(match maybe_successor {
-        None => warp::reply::with_status("", warp::http::StatusCode::NOT_FOUND).into_response(),
-        Some(successor) => successor.name().to_string().into_response(),
-    })
-}
+    env_logger::Builder::new()
+        .filter_level(config.log_level)
+        .init();
 
-/// POST /kill/{name} => 200 OK with body "Killed {name} successfully"
-pub fn kill_person_route(
-    post_lineage_ref: Arc<RwLock<Lineage>>,
-) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    warp::post() // only post requests
-        .and(warp::path!("kill")) // only matching kill path
-        .and(warp::query::<NameQueryParam>()) // only having a name query parameter
-        .and(warp::any().map(move || post_lineage_ref.clone()))
-        .and_then(kill_person)
-}
+    // these work on the files alone
+    match &config.command {
+        Command::Lint => exit_with(cli::lint(&config.datasets, &config.aliases)),
+        Command::Convert { input, output } => exit_with(cli::convert(input, output)),
+        _ => {}
+    }
 
-/// This cant be inline because async closures are unstable for now, needs to be a standalone function
-async fn kill_person(
-    query: NameQueryParam,
-    lineage: Arc<RwLock<Lineage>>,
-) -> Result<impl warp::Reply, Infallible> {
-    let killed = lineage.write().await.kill(&query.name);
-    Ok(match killed {
-        Ok(()) => format!("Killed {} successfully", query.name).into_response(),
-        Err(e) => match e {
-            KillError::PersonNotFound => {
-                warp::reply::with_status("", warp::http::StatusCode::NOT_FOUND).into_response()
-            }
-            KillError::PersonAlreadyDead => {
-                format!("{} was already dead", query.name).into_response()
-            }
-        },
-    })
-}
+    let mut lineage = load_lineage(&config).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1)
+    });
+    let journal = match (&config.command, &config.state_file) {
+        (Command::Serve, _) | (Command::Kill { .. }, _) => {
+            persistence::open_journal(&config.state_file, &mut lineage)
+        }
+        // the other commands only read the state, the file is not created for them
+        (_, Some(path)) => persistence::replay(path, &mut lineage).map(|()| Journal::disabled()),
+        (_, None) => Ok(Journal::disabled()),
+    }
+    .unwrap_or_else(|e| {
+        eprintln!("Could not open state file: {}", e);
+        std::process::exit(1)
+    });
 
-#[tokio::main]
-async fn main() {
-    let lineage = read_lineage_from_file("got_families.csv");
+    match &config.command {
+        Command::Serve => {}
+        Command::Successor { name } => exit_with(cli::successor(&lineage, name)),
+        Command::Line { name, count } => exit_with(cli::line(&lineage, name, *count)),
+        Command::Kill { names } => exit_with(cli::kill(&mut lineage, &journal, names)),
+        Command::Graph(args) => exit_with(cli::graph(&lineage, args)),
+        // starts from the persisted state, without recording its own changes
+        Command::Repl => exit_with(repl::run(lineage)),
+        Command::Lint | Command::Convert { .. } => unreachable!("handled before loading"),
+    }
 
     // To synchronize reads and writes to the lineage between tasks and threads
     let lineage_shared = Arc::new(RwLock::new(lineage));
+    // changes are persisted and the heir changes they cause published to the subscribers
+    let changes = Arc::new(api::ChangeLog::new(journal));
 
-    // each route needs a handle to the lineage in order to query or modify it
-    let get_successor_lineage_ref = lineage_shared.clone();
-    let kill_person_lineage_ref = lineage_shared.clone();
-
-    let routes = get_successor_route(get_successor_lineage_ref)
-        .or(kill_person_route(kill_person_lineage_ref));
+    let routes = api::routes(lineage_shared, changes).with(warp::log("craft_ai_project"));
 
-    // warp runs in a single thread by default, but can be made to run in as many as needed
+    // warp runs on as many worker threads as configured
     // https://github.com/seanmonstar/warp/issues/557#issuecomment-622323015
-    warp::serve(routes).run(([127, 0, 0, 1], 3030)).await;
+    let mut runtime = tokio::runtime::Builder::new()
+        .threaded_scheduler()
+        .core_threads(config.workers)
+        .enable_all()
+        .build()
+        .expect("Could not start the runtime");
+    log::info!("Listening on {}", config.socket_addr());
+    runtime.block_on(warp::serve(routes).run(config.socket_addr()));
 }
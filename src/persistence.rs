@@ -0,0 +1,231 @@
+use crate::lineage::{Lineage, ParentChildInfo, Sex};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// A change applied to the lineage after it was loaded from the datasets. People are referred to
+/// by id, journals written before ids existed refer to them by name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    Kill {
+        #[serde(alias = "name")]
+        id: String,
+    },
+    Revive {
+        #[serde(alias = "name")]
+        id: String,
+    },
+    AddPerson {
+        name: String,
+        sex: Sex,
+        #[serde(default)]
+        id: Option<String>,
+    },
+    AddRelationship(ParentChildInfo),
+    RemoveRelationship {
+        #[serde(alias = "parent_name")]
+        parent_id: String,
+        #[serde(alias = "child_name")]
+        child_id: String,
+    },
+}
+
+impl Event {
+    /// Applies the event to the lineage, events which no longer apply (killing someone already
+    /// dead for example) are ignored
+    pub fn apply(&self, lineage: &mut Lineage) {
+        match self {
+            Event::Kill { id } => {
+                let _ = lineage.kill(id);
+            }
+            Event::Revive { id } => {
+                let _ = lineage.revive(id);
+            }
+            Event::AddPerson { name, sex, id } => {
+                let _ = lineage.add_person(name, sex.clone(), id.as_deref());
+            }
+            Event::AddRelationship(parent_child_info) => {
+                let _ = lineage.load(parent_child_info.clone());
+            }
+            Event::RemoveRelationship {
+                parent_id,
+                child_id,
+            } => {
+                let _ = lineage.remove_relationship(parent_id, child_id);
+            }
+        }
+    }
+}
+
+/// Append only log of the changes made to the lineage, one JSON event per line. Replaying it on
+/// top of the datasets restores the state of the lineage.
+#[derive(Debug)]
+pub struct Journal {
+    file: Option<Mutex<File>>,
+}
+
+impl Journal {
+    /// A journal which does not persist anything, changes are kept in memory only
+    pub fn disabled() -> Self {
+        Journal { file: None }
+    }
+
+    /// Whether the events are saved to a file
+    pub fn is_enabled(&self) -> bool {
+        self.file.is_some()
+    }
+
+    /// Replays the events stored at `path` into `lineage` and opens it for appending new events.
+    /// The file is created if it does not exist.
+    pub fn open(path: &Path, lineage: &mut Lineage) -> Result<Self, String> {
+        let error = |e: std::io::Error| format!("could not use {}: {}", path.display(), e);
+        replay(path, lineage)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(error)?;
+        Ok(Journal {
+            file: Some(Mutex::new(file)),
+        })
+    }
+
+    /// Should be called while holding the lineage write lock so events are stored in the same
+    /// order they were applied
+    pub fn record(&self, event: &Event) -> std::io::Result<()> {
+        if let Some(file) = &self.file {
+            let mut line = serde_json::to_string(event)?;
+            line.push('\n');
+            let mut file = file.lock().expect("journal lock poisoned");
+            file.write_all(line.as_bytes())?;
+            file.flush()?;
+        }
+        Ok(())
+    }
+}
+
+fn read_events(
+    path: &Path,
+) -> std::io::Result<impl Iterator<Item = Result<Event, serde_json::Error>>> {
+    let reader = BufReader::new(File::open(path)?);
+    let lines = reader.lines().collect::<std::io::Result<Vec<String>>>()?;
+    Ok(lines
+        .into_iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(&line)))
+}
+
+/// Replays the events stored at `path` into `lineage`, if the file exists, without opening it for
+/// new events
+pub fn replay(path: &Path, lineage: &mut Lineage) -> Result<(), String> {
+    if path.exists() {
+        let events =
+            read_events(path).map_err(|e| format!("could not use {}: {}", path.display(), e))?;
+        for event in events {
+            event
+                .map_err(|e| format!("corrupted {}: {}", path.display(), e))?
+                .apply(lineage);
+        }
+    }
+    Ok(())
+}
+
+/// Returns the journal configured at `state_file`, or a disabled one
+pub fn open_journal(
+    state_file: &Option<PathBuf>,
+    lineage: &mut Lineage,
+) -> Result<Journal, String> {
+    match state_file {
+        Some(path) => Journal::open(path, lineage),
+        None => Ok(Journal::disabled()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lineage::{ParentChildInfo, Sex};
+
+    fn lineage() -> Lineage {
+        let mut lineage = Lineage::new();
+        lineage.insert(ParentChildInfo::new(
+            "Father House",
+            Sex::Male,
+            "Son House",
+            Sex::Male,
+        ));
+        lineage
+    }
+
+    #[test]
+    fn replays_recorded_events() {
+        let path = std::env::temp_dir().join(format!("journal-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut first_run = lineage();
+        let journal = Journal::open(&path, &mut first_run).unwrap();
+        let events = vec![
+            Event::Kill {
+                id: "son-house".to_string(),
+            },
+            Event::AddPerson {
+                name: "Stranger Other".to_string(),
+                sex: Sex::Female,
+                id: None,
+            },
+            Event::AddRelationship(ParentChildInfo::new(
+                "Father House",
+                Sex::Male,
+                "Daughter House",
+                Sex::Female,
+            )),
+            Event::RemoveRelationship {
+                parent_id: "Father House".to_string(),
+                child_id: "Son House".to_string(),
+            },
+        ];
+        for event in &events {
+            event.apply(&mut first_run);
+            journal.record(event).unwrap();
+        }
+        drop(journal);
+
+        let mut second_run = lineage();
+        Journal::open(&path, &mut second_run).unwrap();
+        assert_eq!(first_run.people(), second_run.people());
+        assert!(!second_run.get_from_name("Son House").unwrap().alive());
+        assert!(second_run.get_from_name("Father House").unwrap().alive());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn reads_events_referring_to_names() {
+        let kill: Event = serde_json::from_str(r#"{"event":"kill","name":"Son House"}"#).unwrap();
+        assert_eq!(
+            kill,
+            Event::Kill {
+                id: "Son House".to_string()
+            }
+        );
+        let mut lineage = lineage();
+        kill.apply(&mut lineage);
+        assert!(!lineage.get_from_id("son-house").unwrap().alive());
+    }
+
+    #[test]
+    fn replaying_does_not_create_the_file() {
+        let path = std::env::temp_dir().join(format!("replay-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut lineage = lineage();
+        replay(&path, &mut lineage).unwrap();
+        assert!(!path.exists());
+
+        std::fs::write(&path, "{\"event\":\"kill\",\"id\":\"son-house\"}\n").unwrap();
+        replay(&path, &mut lineage).unwrap();
+        assert!(!lineage.get_from_id("son-house").unwrap().alive());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,348 @@
+//! Interactive shell to explore a lineage and play what-if scenarios. Killing people only changes
+//! the lineage of the session, never the state file nor a running server, and can be undone.
+use crate::cli;
+use crate::lineage::{KillError, Lineage, Person};
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+
+const HELP: &str = "\
+who-succeeds {name}    the heir of the person
+line {name} [count]    the heirs of the person in order
+relatives {name}       the close relatives of the person
+kill {name}            kills the person, telling whose heir changed
+undo                   revives the last person killed
+help                   this message
+exit                   leaves, Ctrl-D too";
+
+/// Commands completed at the start of the line
+const COMMANDS: &[&str] = &[
+    "who-succeeds",
+    "line",
+    "relatives",
+    "kill",
+    "undo",
+    "help",
+    "exit",
+];
+
+/// The lineage of the session and what was done to it
+pub struct Session {
+    lineage: Lineage,
+    /// ids of the people killed, the last one first to be revived by `undo`
+    killed: Vec<String>,
+}
+
+/// What to do after a line
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Print(String),
+    Exit,
+}
+
+impl Session {
+    pub fn new(lineage: Lineage) -> Self {
+        Session {
+            lineage,
+            killed: vec![],
+        }
+    }
+
+    pub fn lineage(&self) -> &Lineage {
+        &self.lineage
+    }
+
+    /// Runs a line typed by the user, telling what to print or why it failed
+    pub fn run(&mut self, line: &str) -> Result<Outcome, String> {
+        let line = line.trim();
+        let (command, argument) = match line.find(char::is_whitespace) {
+            Some(i) => (&line[..i], line[i..].trim()),
+            None => (line, ""),
+        };
+        let print = |output: String| Ok(Outcome::Print(output));
+        let name = || {
+            if argument.is_empty() {
+                Err(format!("{} needs a name, see help", command))
+            } else {
+                Ok(argument)
+            }
+        };
+        match command {
+            "" => print(String::new()),
+            "who-succeeds" => print(cli::successor(&self.lineage, name()?)?),
+            "line" => {
+                // a trailing number is the count, names do not end with one
+                let name = name()?;
+                let (name, count) = match name.rsplit_once(' ') {
+                    Some((rest, count)) if count.parse::<usize>().is_ok() => {
+                        (rest.trim_end(), count.parse().ok())
+                    }
+                    _ => (name, None),
+                };
+                print(cli::line(&self.lineage, name, count)?)
+            }
+            "relatives" => print(self.relatives(name()?)?),
+            "kill" => print(self.kill(name()?)?),
+            "undo" => print(self.undo()?),
+            "help" => print(HELP.to_string()),
+            "exit" | "quit" => Ok(Outcome::Exit),
+            _ => Err(format!("unknown command {}, see help", command)),
+        }
+    }
+
+    fn kill(&mut self, name: &str) -> Result<String, String> {
+        let person = self.lineage.resolve(name).map_err(|e| e.to_string())?;
+        let (id, name) = (person.id().to_string(), person.name().to_string());
+        match self.lineage.kill(&id) {
+            Ok(changes) => {
+                self.killed.push(id);
+                Ok(std::iter::once(format!("Killed {}", name))
+                    .chain(changes.iter().map(cli::describe_change))
+                    .collect::<Vec<_>>()
+                    .join("\n"))
+            }
+            Err(KillError::PersonAlreadyDead) => Err(format!("{} is already dead", name)),
+            Err(KillError::PersonNotFound) => unreachable!("the person was resolved"),
+        }
+    }
+
+    fn undo(&mut self) -> Result<String, String> {
+        let id = self.killed.pop().ok_or("nothing to undo")?;
+        let changes = self
+            .lineage
+            .revive(&id)
+            .expect("the people killed in the session are dead");
+        let name = self.lineage.resolve(&id).expect("ids resolve").name();
+        Ok(std::iter::once(format!("Revived {}", name))
+            .chain(changes.iter().map(cli::describe_change))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    fn relatives(&self, name: &str) -> Result<String, String> {
+        let lineage = &self.lineage;
+        let person = lineage.resolve(name).map_err(|e| e.to_string())?;
+        let groups = vec![
+            (
+                "Father",
+                lineage.get_father_of(person).into_iter().collect(),
+            ),
+            (
+                "Mother",
+                lineage.get_mother_of(person).into_iter().collect(),
+            ),
+            ("Brothers", lineage.get_brothers(person)),
+            ("Sisters", lineage.get_sisters(person)),
+            ("Sons", lineage.get_sons_of(person)),
+            ("Daughters", lineage.get_daughters_of(person)),
+            ("Grandparents", lineage.get_grandparents(person)),
+            ("Grandchildren", lineage.get_grandchildren(person)),
+            ("Uncles", lineage.get_uncles(person)),
+            ("Aunts", lineage.get_aunts(person)),
+            ("Nephews", lineage.get_nephews(person)),
+            ("Nieces", lineage.get_nieces(person)),
+            ("Cousins", lineage.get_cousins(person, 1)),
+        ];
+        let lines: Vec<String> = groups
+            .into_iter()
+            .filter(|(_, people)| !people.is_empty())
+            .map(|(label, mut people): (_, Vec<&Person>)| {
+                people.sort();
+                let names: Vec<String> = people
+                    .iter()
+                    .map(|person| {
+                        if person.alive() {
+                            person.name().to_string()
+                        } else {
+                            format!("{} †", person.name())
+                        }
+                    })
+                    .collect();
+                format!("{}: {}", label, names.join(", "))
+            })
+            .collect();
+        if lines.is_empty() {
+            Ok(format!("{} has no known relative", person.name()))
+        } else {
+            Ok(lines.join("\n"))
+        }
+    }
+}
+
+/// Completes the commands, and the names after them
+struct NameCompleter {
+    names: Vec<String>,
+}
+
+impl NameCompleter {
+    /// The start of what is completed, and the candidates
+    fn candidates(&self, line: &str) -> (usize, Vec<String>) {
+        let starting_with = |candidates: &mut dyn Iterator<Item = &str>, start: &str| {
+            let start = start.to_lowercase();
+            candidates
+                .filter(|candidate| candidate.to_lowercase().starts_with(&start))
+                .map(str::to_string)
+                .collect()
+        };
+        match line.find(' ') {
+            None => (0, starting_with(&mut COMMANDS.iter().cloned(), line)),
+            Some(i) => {
+                let start = line[i..].len() - line[i..].trim_start().len() + i;
+                (
+                    start,
+                    starting_with(&mut self.names.iter().map(String::as_str), &line[start..]),
+                )
+            }
+        }
+    }
+}
+
+impl Completer for NameCompleter {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.candidates(&line[..pos]))
+    }
+}
+
+impl Hinter for NameCompleter {
+    type Hint = String;
+}
+
+impl Highlighter for NameCompleter {}
+
+impl Validator for NameCompleter {}
+
+impl Helper for NameCompleter {}
+
+/// Reads and runs commands until exit or end of input, errors being printed without leaving.
+/// History only lasts as long as the session.
+pub fn run(lineage: Lineage) -> Result<String, String> {
+    let mut editor = Editor::<NameCompleter>::new();
+    editor.set_helper(Some(NameCompleter {
+        names: lineage.names().into_iter().map(str::to_string).collect(),
+    }));
+    let mut session = Session::new(lineage);
+    println!("Type help for the commands, Tab completes names");
+    loop {
+        match editor.readline("> ") {
+            Ok(line) => {
+                editor.add_history_entry(line.as_str());
+                match session.run(&line) {
+                    Ok(Outcome::Print(output)) if output.is_empty() => {}
+                    Ok(Outcome::Print(output)) => println!("{}", output),
+                    Ok(Outcome::Exit) => return Ok(String::new()),
+                    Err(e) => eprintln!("{}", e),
+                }
+            }
+            // Ctrl-C only drops the line being typed
+            Err(ReadlineError::Interrupted) => {}
+            Err(ReadlineError::Eof) => return Ok(String::new()),
+            Err(e) => return Err(format!("could not read the command: {}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lineage::{
+        ParentChildInfo,
+        Sex::{Female, Male},
+    };
+
+    /// A father with a son and a daughter
+    fn session() -> Session {
+        let mut lineage = Lineage::new();
+        for (child, sex) in &[("Son House", Male), ("Daughter House", Female)] {
+            lineage.insert(ParentChildInfo::new(
+                "Father House",
+                Male,
+                *child,
+                sex.clone(),
+            ));
+        }
+        Session::new(lineage)
+    }
+
+    fn print(output: &str) -> Result<Outcome, String> {
+        Ok(Outcome::Print(output.to_string()))
+    }
+
+    #[test]
+    fn answers_the_commands() {
+        let mut session = session();
+        assert_eq!(session.run("who-succeeds Father House"), print("Son House"));
+        assert_eq!(
+            session.run("  line   father house 1 "),
+            print("1. Son House")
+        );
+        assert_eq!(
+            session.run("relatives Son House"),
+            print("Father: Father House\nSisters: Daughter House")
+        );
+        assert!(session.run("who-succeeds").is_err());
+        assert!(session.run("dance Father House").is_err());
+        assert_eq!(session.run("exit"), Ok(Outcome::Exit));
+    }
+
+    #[test]
+    fn undoes_the_deaths_in_reverse_order() {
+        let mut session = session();
+        assert_eq!(
+            session.run("kill Son House"),
+            print(
+                "Killed Son House\n\
+                 Daughter House: Son House → Father House\n\
+                 Father House: Son House → Daughter House"
+            )
+        );
+        assert!(session.run("kill Son House").is_err());
+        session.run("kill Daughter House").unwrap();
+        assert_eq!(
+            session.run("relatives Father House"),
+            print("Sons: Son House †\nDaughters: Daughter House †")
+        );
+
+        assert_eq!(
+            session.run("undo"),
+            print("Revived Daughter House\nFather House: nobody → Daughter House")
+        );
+        assert_eq!(
+            session.run("who-succeeds Father House"),
+            print("Daughter House")
+        );
+        session.run("undo").unwrap();
+        assert_eq!(session.run("who-succeeds Father House"), print("Son House"));
+        assert_eq!(session.run("undo"), Err("nothing to undo".to_string()));
+    }
+
+    #[test]
+    fn completes_commands_then_names() {
+        let completer = NameCompleter {
+            names: session()
+                .lineage()
+                .names()
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        };
+        assert_eq!(
+            completer.candidates("wh"),
+            (0, vec!["who-succeeds".to_string()])
+        );
+        assert_eq!(
+            completer.candidates("kill  s"),
+            (6, vec!["Son House".to_string()])
+        );
+        assert_eq!(completer.candidates("line Nobody").1, Vec::<String>::new());
+    }
+}